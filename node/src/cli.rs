@@ -1,3 +1,4 @@
+use crate::{kitty_cmd::KittySubcommand, poe_cmd::PoeSubcommand};
 use sc_cli::RunCmd;
 
 #[derive(Debug, clap::Parser)]
@@ -50,4 +51,12 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Kitty utilities.
+	#[clap(subcommand)]
+	Kitty(KittySubcommand),
+
+	/// Proof-of-existence utilities.
+	#[clap(subcommand)]
+	Poe(PoeSubcommand),
 }
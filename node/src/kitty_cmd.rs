@@ -0,0 +1,86 @@
+//! The `kitty` sub-commands.
+
+use crate::{kitty_render::render_svg, service::FullClient};
+use pallet_kitties_runtime_api::KittiesApi;
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use std::{fs, path::PathBuf, sync::Arc};
+
+#[derive(Debug, clap::Subcommand)]
+pub enum KittySubcommand {
+	/// Render kitties from their on-chain DNA into SVG files.
+	Render(RenderCmd),
+}
+
+/// The `kitty render` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RenderCmd {
+	/// Ids of the kitties to render, as `<collection>/<index>`. A bare index refers to the
+	/// default collection `0`.
+	#[clap(required = true, parse(try_from_str = parse_kitty_id))]
+	pub kitty_ids: Vec<(u32, u32)>,
+
+	/// Directory the SVG files are written to, as `kitty-<collection>-<index>.svg`.
+	#[clap(long, short, default_value = ".", parse(from_os_str))]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl RenderCmd {
+	/// Render the requested kitties at the best block.
+	pub fn run(&self, client: Arc<FullClient>) -> sc_cli::Result<()> {
+		let at = BlockId::hash(client.info().best_hash);
+		fs::create_dir_all(&self.output)?;
+
+		for &(collection_id, kitty_index) in &self.kitty_ids {
+			let (dna, gender) = client
+				.runtime_api()
+				.kitty(&at, (collection_id, kitty_index))
+				.map_err(|e| {
+					format!("Unable to query kitty {}/{}: {}", collection_id, kitty_index, e)
+				})?
+				.ok_or_else(|| format!("Kitty {}/{} does not exist", collection_id, kitty_index))?;
+
+			let path = self.output.join(format!("kitty-{}-{}.svg", collection_id, kitty_index));
+			fs::write(&path, render_svg(&dna, &gender))?;
+			println!("{}", path.display());
+		}
+
+		Ok(())
+	}
+}
+
+fn parse_kitty_id(s: &str) -> Result<(u32, u32), String> {
+	let parse = |n: &str| n.parse::<u32>().map_err(|e| format!("invalid kitty id {}: {}", s, e));
+	match s.split_once('/') {
+		Some((collection_id, kitty_index)) => Ok((parse(collection_id)?, parse(kitty_index)?)),
+		None => Ok((0, parse(s)?)),
+	}
+}
+
+impl CliConfiguration for RenderCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
@@ -0,0 +1,202 @@
+//! Deterministic SVG rendering of kitties.
+//!
+//! Every visual trait is derived from a fixed byte of the kitty's DNA, so the same DNA and gender
+//! always produce byte-for-byte identical output. Rendering is pure Rust and never touches the
+//! network or the file system.
+
+use pallet_kitties_runtime_api::Gender;
+
+/// Width and height of the rendered image, in pixels.
+pub const IMAGE_SIZE: u32 = 256;
+
+// DNA byte assignments.
+const BACKGROUND_HUE: usize = 0;
+const FUR_HUE: usize = 1;
+const FUR_SATURATION: usize = 2;
+const EAR_HEIGHT: usize = 3;
+const EYE_HUE: usize = 4;
+const EYE_SIZE: usize = 5;
+const STRIPES: usize = 6;
+const WHISKERS: usize = 7;
+const TAIL_CURL: usize = 8;
+const ACCESSORY_HUE: usize = 9;
+const MOUTH: usize = 10;
+
+// Map a DNA byte onto `[min, min + span)`.
+fn scale(byte: u8, min: u32, span: u32) -> u32 {
+	min + u32::from(byte) * span / 256
+}
+
+fn hsl(hue_byte: u8, saturation: u32, lightness: u32) -> String {
+	format!("hsl({},{}%,{}%)", scale(hue_byte, 0, 360), saturation, lightness)
+}
+
+/// Render a kitty as an SVG document.
+pub fn render_svg(dna: &[u8; 16], gender: &Gender) -> String {
+	let fur_saturation = scale(dna[FUR_SATURATION], 30, 50);
+	let fur = hsl(dna[FUR_HUE], fur_saturation, 60);
+	let fur_dark = hsl(dna[FUR_HUE], fur_saturation, 35);
+	let accessory = hsl(dna[ACCESSORY_HUE], 70, 50);
+
+	let mut svg = String::new();
+	let mut w = |s: String| svg.push_str(&s);
+
+	w(format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 256 256\">",
+		IMAGE_SIZE
+	));
+	w(format!(
+		"<rect width=\"256\" height=\"256\" fill=\"{}\"/>",
+		hsl(dna[BACKGROUND_HUE], 40, 90)
+	));
+
+	// Tail, drawn first so the body covers its base.
+	let curl = scale(dna[TAIL_CURL], 0, 80);
+	w(format!(
+		"<path d=\"M180 200 Q230 {} {} 110\" stroke=\"{}\" stroke-width=\"14\" fill=\"none\" stroke-linecap=\"round\"/>",
+		200 - curl,
+		200 + curl / 2,
+		fur_dark
+	));
+
+	// Body and head.
+	w(format!("<ellipse cx=\"128\" cy=\"190\" rx=\"62\" ry=\"50\" fill=\"{}\"/>", fur));
+	let ear_height = scale(dna[EAR_HEIGHT], 25, 35);
+	for (base, tip) in [(78, 84), (178, 172)] {
+		let inner = if base < 128 { base + 40 } else { base - 40 };
+		w(format!(
+			"<polygon points=\"{},100 {},{} {},80\" fill=\"{}\"/>",
+			base,
+			tip,
+			75 - ear_height,
+			inner,
+			fur
+		));
+	}
+	w(format!("<circle cx=\"128\" cy=\"110\" r=\"55\" fill=\"{}\"/>", fur));
+
+	// Forehead stripes.
+	let stripes = dna[STRIPES] % 5;
+	for i in 0..stripes {
+		let x = 128 + 12 * i32::from(i) - 6 * i32::from(stripes - 1);
+		w(format!(
+			"<line x1=\"{0}\" y1=\"58\" x2=\"{0}\" y2=\"78\" stroke=\"{1}\" stroke-width=\"4\" stroke-linecap=\"round\"/>",
+			x, fur_dark
+		));
+	}
+
+	// Eyes.
+	let eye_radius = scale(dna[EYE_SIZE], 7, 8);
+	let eye = hsl(dna[EYE_HUE], 70, 45);
+	for cx in [106, 150] {
+		w(format!(
+			"<circle cx=\"{0}\" cy=\"105\" r=\"{1}\" fill=\"white\"/><circle cx=\"{0}\" cy=\"105\" r=\"{2}\" fill=\"{3}\"/>",
+			cx,
+			eye_radius + 3,
+			eye_radius,
+			eye
+		));
+	}
+
+	// Nose, mouth and whiskers.
+	w("<polygon points=\"122,124 134,124 128,131\" fill=\"#e88\"/>".to_string());
+	let smile = scale(dna[MOUTH], 131, 12);
+	w(format!(
+		"<path d=\"M116 134 Q122 {0} 128 131 Q134 {0} 140 134\" stroke=\"#333\" stroke-width=\"2\" fill=\"none\"/>",
+		smile
+	));
+	let whiskers = dna[WHISKERS] % 3 + 1;
+	for i in 0..u32::from(whiskers) {
+		let y = 124 + 6 * i;
+		w(format!(
+			"<line x1=\"112\" y1=\"{0}\" x2=\"78\" y2=\"{1}\" stroke=\"#333\"/><line x1=\"144\" y1=\"{0}\" x2=\"178\" y2=\"{1}\" stroke=\"#333\"/>",
+			y,
+			y + 4 * i - 4
+		));
+	}
+
+	// Gender accessory: a bow tie for males, a hair bow for females.
+	match gender {
+		Gender::Male => w(format!(
+			"<polygon points=\"108,158 128,168 108,178\" fill=\"{0}\"/><polygon points=\"148,158 128,168 148,178\" fill=\"{0}\"/>",
+			accessory
+		)),
+		Gender::Female => w(format!(
+			"<polygon points=\"150,60 166,52 166,72\" fill=\"{0}\"/><polygon points=\"182,60 166,52 166,72\" fill=\"{0}\"/><circle cx=\"166\" cy=\"62\" r=\"4\" fill=\"{0}\"/>",
+			accessory
+		)),
+	}
+
+	svg.push_str("</svg>");
+	svg
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// 检查每个标签都正确闭合，且标签之外没有多余的文本
+	fn assert_well_formed(svg: &str) {
+		let mut open = Vec::new();
+		let mut rest = svg;
+		while !rest.is_empty() {
+			assert!(rest.starts_with('<'), "text outside of a tag: {}", rest);
+			let end = rest.find('>').expect("unterminated tag");
+			let tag = &rest[1..end];
+			assert!(!tag.contains('<'), "nested tag: {}", tag);
+			if let Some(name) = tag.strip_prefix('/') {
+				assert_eq!(open.pop(), Some(name), "mismatched closing tag");
+			} else if !tag.ends_with('/') {
+				open.push(tag.split(' ').next().unwrap());
+			}
+			rest = &rest[end + 1..];
+		}
+		assert!(open.is_empty(), "unclosed tags: {:?}", open);
+	}
+
+	// 相同的 DNA 输出逐字节相同的 SVG
+	#[test]
+	fn render_is_deterministic() {
+		let dna = [7u8; 16];
+		assert_eq!(render_svg(&dna, &Gender::Male), render_svg(&dna, &Gender::Male));
+		assert_eq!(render_svg(&dna, &Gender::Female), render_svg(&dna, &Gender::Female));
+	}
+
+	// 不同的 DNA 或性别输出不同的 SVG
+	#[test]
+	fn render_depends_on_dna_and_gender() {
+		let dna = [0u8; 16];
+		let base = render_svg(&dna, &Gender::Male);
+		for index in [
+			BACKGROUND_HUE,
+			FUR_HUE,
+			FUR_SATURATION,
+			EAR_HEIGHT,
+			EYE_HUE,
+			EYE_SIZE,
+			STRIPES,
+			WHISKERS,
+			TAIL_CURL,
+			ACCESSORY_HUE,
+			MOUTH,
+		] {
+			let mut other = dna;
+			other[index] = 0xfe;
+			assert_ne!(render_svg(&other, &Gender::Male), base, "dna byte {}", index);
+		}
+		assert_ne!(render_svg(&dna, &Gender::Female), base);
+	}
+
+	// 输出是完整且标签平衡的 SVG 文档
+	#[test]
+	fn render_is_well_formed() {
+		for byte in [0u8, 1, 127, 128, 254, 255] {
+			for gender in [Gender::Male, Gender::Female] {
+				let svg = render_svg(&[byte; 16], &gender);
+				assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+				assert!(svg.ends_with("</svg>"));
+				assert_well_formed(&svg);
+			}
+		}
+	}
+}
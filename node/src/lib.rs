@@ -1,3 +1,4 @@
 pub mod chain_spec;
+pub mod kitty_render;
 pub mod rpc;
 pub mod service;
@@ -7,8 +7,12 @@ mod service;
 mod cli;
 mod command;
 mod command_helper;
+mod kitty_cmd;
+mod poe_cmd;
 mod rpc;
 
+use node_template::kitty_render;
+
 fn main() -> sc_cli::Result<()> {
 	command::run()
 }
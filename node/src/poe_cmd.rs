@@ -0,0 +1,117 @@
+//! The `poe` sub-commands.
+
+use node_template_runtime::{Hash, Runtime};
+use pallet_poe::merkle;
+use std::{fs, path::PathBuf};
+
+type Poe = pallet_poe::Pallet<Runtime>;
+type Hashing = <Runtime as frame_system::Config>::Hashing;
+
+#[derive(Debug, clap::Subcommand)]
+pub enum PoeSubcommand {
+	/// Compute the digests files are claimed under, without uploading them.
+	Hash(HashCmd),
+	/// Build the Merkle tree of a directory of files for `anchor_batch`, with a proof per file.
+	Batch(BatchCmd),
+}
+
+/// The `poe hash` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct HashCmd {
+	/// Files to hash.
+	#[clap(required = true, parse(from_os_str))]
+	pub files: Vec<PathBuf>,
+
+	/// Secret 32-byte salt, hex encoded. Prints the salted commitment to claim and, after it,
+	/// the digest to pass to `reveal` with the salt.
+	#[clap(long)]
+	pub salt: Option<String>,
+}
+
+impl HashCmd {
+	/// Print the claim digest of every file, as passed to `create_claim`.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let salt = self.salt.as_deref().map(parse_salt).transpose()?;
+		for path in &self.files {
+			let document = fs::read(path)?;
+			let claim = Poe::claim_hash(&document);
+			match &salt {
+				Some(salt) => {
+					println!("{:?} {:?}  {}", Poe::commitment(salt, &claim), claim, path.display())
+				},
+				None => println!("{:?}  {}", claim, path.display()),
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn parse_salt(salt: &str) -> sc_cli::Result<[u8; 32]> {
+	let bytes = sp_core::bytes::from_hex(salt)
+		.map_err(|e| sc_cli::Error::Input(format!("invalid salt: {}", e)))?;
+	bytes
+		.try_into()
+		.map_err(|_| sc_cli::Error::Input("the salt must be 32 bytes".into()))
+}
+
+/// The `poe batch` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct BatchCmd {
+	/// Directory of the files to anchor. Sub-directories are skipped.
+	#[clap(parse(from_os_str))]
+	pub dir: PathBuf,
+
+	/// Directory to write the proof of every file to, as `<file name>.proof`.
+	#[clap(long, parse(from_os_str))]
+	pub proofs: Option<PathBuf>,
+}
+
+impl BatchCmd {
+	/// Print the Merkle root and leaf count to pass to `anchor_batch`, and the claim digest of
+	/// every file. The leaves are the files sorted by path.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let mut paths = Vec::new();
+		for entry in fs::read_dir(&self.dir)? {
+			let path = entry?.path();
+			if path.is_file() {
+				paths.push(path);
+			}
+		}
+		paths.sort();
+
+		let mut leaves = Vec::with_capacity(paths.len());
+		for path in &paths {
+			leaves.push(Poe::claim_hash(&fs::read(path)?));
+		}
+		let root = merkle::root::<Hashing>(&leaves)
+			.ok_or_else(|| sc_cli::Error::Input(format!("no files in {}", self.dir.display())))?;
+
+		println!("root {:?}", root);
+		println!("leaves {}", leaves.len());
+		for (leaf, path) in leaves.iter().zip(&paths) {
+			println!("{:?}  {}", leaf, path.display());
+		}
+
+		if let Some(dir) = &self.proofs {
+			fs::create_dir_all(dir)?;
+			for (index, path) in paths.iter().enumerate() {
+				let proof = merkle::proof::<Hashing>(&leaves, index).unwrap_or_default();
+				let mut name = path.file_name().unwrap_or_default().to_os_string();
+				name.push(".proof");
+				fs::write(dir.join(name), proof_file(root, leaves[index], &proof))?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+// The root, the leaf and then the siblings from the leaf up, one per line: the arguments of
+// `PoeApi::verify_inclusion`.
+fn proof_file(root: Hash, leaf: Hash, proof: &[Hash]) -> String {
+	let mut lines = vec![format!("{:?}", root), format!("{:?}", leaf)];
+	lines.extend(proof.iter().map(|sibling| format!("{:?}", sibling)));
+	lines.push(String::new());
+	lines.join("\n")
+}
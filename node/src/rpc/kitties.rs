@@ -0,0 +1,84 @@
+//! RPC methods of the kitties pallet that need the node, such as rendering.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{AccountId, Balance, BlockNumber};
+use pallet_kitties_runtime_api::KittiesApi as KittiesRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use crate::kitty_render::render_svg;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+/// Error code returned when the requested kitty does not exist.
+const KITTY_NOT_FOUND: i32 = 2;
+
+/// Kitties RPC methods.
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash> {
+	/// Render a kitty of a collection as an SVG document, at the given block or the best block.
+	#[method(name = "kitties_render")]
+	fn render(
+		&self,
+		collection_id: u32,
+		kitty_index: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<String>;
+}
+
+/// Provides the kitties RPC methods.
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	/// Create a new instance of the kitties RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> KittiesApiServer<<Block as BlockT>::Hash> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, (u32, u32), Balance, BlockNumber>,
+{
+	fn render(
+		&self,
+		collection_id: u32,
+		kitty_index: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<String> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let kitty =
+			self.client
+				.runtime_api()
+				.kitty(&at, (collection_id, kitty_index))
+				.map_err(|e| {
+					CallError::Custom(ErrorObject::owned(
+						RUNTIME_ERROR,
+						"Unable to query kitty.",
+						Some(e.to_string()),
+					))
+				})?;
+		let (dna, gender) = kitty.ok_or_else(|| {
+			CallError::Custom(ErrorObject::owned(
+				KITTY_NOT_FOUND,
+				"Kitty does not exist.",
+				None::<()>,
+			))
+		})?;
+
+		Ok(render_svg(&dna, &gender))
+	}
+}
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pallet_kitties_fuzz::{run, Op};
+
+fuzz_target!(|ops: Vec<Op>| run(&ops));
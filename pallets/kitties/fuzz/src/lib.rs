@@ -0,0 +1,256 @@
+//! Fuzzing harness for the kitties marketplace.
+//!
+//! Drives random sequences of kitty extrinsics from several accounts against a mock runtime and
+//! checks the pallet's storage invariants and balance conservation after every step.
+
+use arbitrary::Arbitrary;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Currency, Hooks};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, Dispatchable, IdentityLookup},
+	Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_randomness_collective_flip::Config for Test {}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<1>;
+	type MetadataDepositBase = ConstU64<1>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+pub const STAKE: u64 = 100;
+pub const MINT_PROCEEDS: u64 = 99;
+pub const COLLECTION_DEPOSIT: u64 = 500;
+pub const KITTEN: u64 = 2;
+pub const ADULT: u64 = 8;
+
+impl pallet_kitties::Config for Test {
+	type Event = Event;
+	type KittyRandomness = RandomnessCollectiveFlip;
+	type MaxKittyOwned = ConstU32<3>;
+	type KittyQuota = ();
+	type Currency = Balances;
+	type KittyIndex = u32;
+	type CollectionId = u32;
+	type CollectionDeposit = ConstU64<COLLECTION_DEPOSIT>;
+	type KittyStake = ConstU64<STAKE>;
+	type MaxTotalKitties = ConstU32<100>;
+	type MaxMintsPerBlock = ConstU32<10>;
+	type MintPriceBase = ConstU64<50>;
+	type MintPriceStep = ConstU64<10>;
+	type MintProceedsAccount = ConstU64<MINT_PROCEEDS>;
+	type StatsPeriod = ConstU64<100>;
+	type ListingAssetId = u32;
+	type ListingAssets = Assets;
+	type MaxExpiringListings = ConstU32<10>;
+	type KittenDuration = ConstU64<KITTEN>;
+	type AdultDuration = ConstU64<ADULT>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+}
+
+/// Accounts the operations are sent from.
+pub const ACCOUNTS: u64 = 5;
+pub const INITIAL_BALANCE: u64 = 10_000;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut balances: Vec<_> = (1..=ACCOUNTS).map(|who| (who, INITIAL_BALANCE)).collect();
+	balances.push((MINT_PROCEEDS, 1));
+	pallet_balances::GenesisConfig::<Test> { balances }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// A single step of a fuzzing run. Account and kitty ids are reduced into range, so most
+/// operations hit existing accounts and kitties.
+#[derive(Debug, Arbitrary)]
+pub enum Op {
+	CreateCollection { who: u8, breedable: bool, max_supply: Option<u8>, royalty: u8 },
+	Create { who: u8, collection_id: u8 },
+	SetPrice { who: u8, kitty_id: (u8, u8), price: Option<u16> },
+	Transfer { who: u8, to: u8, kitty_id: (u8, u8) },
+	Buy { who: u8, kitty_id: (u8, u8), bid_price: u16 },
+	Breed { who: u8, parent1: (u8, u8), parent2: (u8, u8) },
+	Retire { who: u8, kitty_id: (u8, u8) },
+	// The listing is signed by `signer`, who may not own the kitty.
+	BuySigned { who: u8, signer: u8, kitty_id: (u8, u8), price: u16, expires_in: u8, nonce: u8 },
+	CancelSigned { who: u8 },
+	NextBlock,
+}
+
+fn account(who: u8) -> u64 {
+	u64::from(who) % ACCOUNTS + 1
+}
+
+// Ids one past the last collection and kitty are kept reachable to exercise
+// `CollectionNotExist` and `KittyNotExist`.
+fn collection(collection_id: u8) -> u32 {
+	u32::from(collection_id) % (Kitties::next_collection_id() + 1)
+}
+
+fn kitty((collection_id, kitty_index): (u8, u8)) -> (u32, u32) {
+	let collection_id = collection(collection_id);
+	(collection_id, u32::from(kitty_index) % (Kitties::collection_supply(collection_id) + 1))
+}
+
+fn apply(op: &Op) {
+	let (who, call) = match *op {
+		Op::CreateCollection { who, breedable, max_supply, royalty } => (
+			who,
+			pallet_kitties::Call::create_collection {
+				breedable,
+				max_supply: max_supply.map(u32::from),
+				royalty: Perbill::from_percent(u32::from(royalty) % 101),
+			},
+		),
+		Op::Create { who, collection_id } => {
+			(who, pallet_kitties::Call::create_kitty { collection_id: collection(collection_id) })
+		},
+		Op::SetPrice { who, kitty_id, price } => (
+			who,
+			pallet_kitties::Call::set_price {
+				kitty_id: kitty(kitty_id),
+				new_price: price.map(u64::from),
+			},
+		),
+		Op::Transfer { who, to, kitty_id } => {
+			(who, pallet_kitties::Call::transfer { to: account(to), kitty_id: kitty(kitty_id) })
+		},
+		Op::Buy { who, kitty_id, bid_price } => (
+			who,
+			pallet_kitties::Call::buy_kitty {
+				kitty_id: kitty(kitty_id),
+				bid_price: u64::from(bid_price),
+			},
+		),
+		Op::Breed { who, parent1, parent2 } => (
+			who,
+			pallet_kitties::Call::breed_kitty { parent1: kitty(parent1), parent2: kitty(parent2) },
+		),
+		Op::Retire { who, kitty_id } => {
+			(who, pallet_kitties::Call::retire_kitty { kitty_id: kitty(kitty_id) })
+		},
+		Op::BuySigned { who, signer, kitty_id, price, expires_in, nonce } => {
+			let listing = pallet_kitties::SignedListing {
+				kitty_id: kitty(kitty_id),
+				price: u64::from(price),
+				expires_at: System::block_number() + u64::from(expires_in),
+				ownership: Kitties::ownership_nonces(kitty(kitty_id)),
+				nonce: u32::from(nonce),
+			};
+			let signature = TestSignature(account(signer), Kitties::listing_message(&listing));
+			(who, pallet_kitties::Call::buy_with_signed_listing { listing, signature })
+		},
+		Op::CancelSigned { who } => (who, pallet_kitties::Call::cancel_signed_listings {}),
+		Op::NextBlock => {
+			let now = System::block_number();
+			Kitties::on_idle(now, u64::MAX);
+			System::set_block_number(now + 1);
+			return;
+		},
+	};
+
+	// Dispatch through the runtime so failed calls are rolled back like in a real block.
+	let _ = Call::Kitties(call).dispatch(Origin::signed(account(who)));
+}
+
+fn check_invariants(total_issuance: u64) {
+	if let Err(e) = Kitties::do_try_state() {
+		panic!("kitties invariant violated: {}", e);
+	}
+
+	let total: u64 = (1..=ACCOUNTS)
+		.chain([MINT_PROCEEDS])
+		.map(|who| Balances::total_balance(&who))
+		.sum();
+	assert_eq!(total, total_issuance, "balances are not conserved");
+	assert_eq!(Balances::total_issuance(), total_issuance, "total issuance changed");
+}
+
+/// Run a sequence of operations from genesis, checking the invariants after every step.
+pub fn run(ops: &[Op]) {
+	new_test_ext().execute_with(|| {
+		let total_issuance = Balances::total_issuance();
+		for op in ops {
+			apply(op);
+			check_invariants(total_issuance);
+		}
+	});
+}
@@ -0,0 +1,32 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_kitties::{Gender, LifeStage, MarketPeriodStats, SaleRecord};
+
+sp_api::decl_runtime_apis! {
+	/// Read access to the kitties marketplace, so the node can serve it without an indexer.
+	pub trait KittiesApi<AccountId, KittyId, Balance, BlockNumber> where
+		AccountId: Codec,
+		KittyId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// DNA and gender of a kitty, identified by collection and index, if it exists.
+		fn kitty(kitty_id: KittyId) -> Option<([u8; 16], Gender)>;
+
+		/// Life stage of a kitty at the best block, which may not be stored yet.
+		fn kitty_stage(kitty_id: KittyId) -> Option<LifeStage>;
+
+		/// The last sale of a kitty, if it was ever bought.
+		fn last_sale(kitty_id: KittyId) -> Option<SaleRecord<AccountId, Balance, BlockNumber>>;
+
+		/// Marketplace aggregates of the given statistics period, with the floor price at the best
+		/// block for the current period.
+		fn market_stats(period: BlockNumber) -> MarketPeriodStats<Balance>;
+
+		/// Index of the statistics period containing the best block.
+		fn current_period() -> BlockNumber;
+	}
+}
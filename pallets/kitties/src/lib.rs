@@ -2,23 +2,79 @@
 
 pub use pallet::*;
 
+use core::marker::PhantomData;
+use frame_support::traits::Get;
+
+/// Per-account limit on the number of Kitties an account may own. The effective limit never
+/// exceeds the storage bound `MaxKittyOwned` and can be overridden per account by root.
+pub trait KittyQuota<AccountId> {
+	fn max_owned(who: &AccountId) -> u32;
+}
+
+/// No limit besides `MaxKittyOwned`.
+impl<AccountId> KittyQuota<AccountId> for () {
+	fn max_owned(_who: &AccountId) -> u32 {
+		u32::MAX
+	}
+}
+
+/// The same limit `Q` for every account.
+pub struct FixedQuota<Q>(PhantomData<Q>);
+
+impl<AccountId, Q: Get<u32>> KittyQuota<AccountId> for FixedQuota<Q> {
+	fn max_owned(_who: &AccountId) -> u32 {
+		Q::get()
+	}
+}
+
+pub mod migrations;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::traits::{
-		tokens::ExistenceRequirement, Currency, Randomness, ReservableCurrency,
+		tokens::{fungibles, ExistenceRequirement},
+		BalanceStatus, Currency, Randomness, ReservableCurrency,
+	};
+	use frame_support::{
+		pallet_prelude::*,
+		sp_std::{collections::btree_map::BTreeMap, vec::Vec},
+		Twox64Concat,
 	};
-	use frame_support::{pallet_prelude::*, Twox64Concat};
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
 	use sp_io::hashing::blake2_128;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded, One};
+	use sp_runtime::{
+		traits::{
+			AtLeast32BitUnsigned, Bounded, CheckedAdd, IdentifyAccount, One, Saturating, Verify,
+			Zero,
+		},
+		Perbill, SaturatedConversion,
+	};
 
 	#[cfg(feature = "std")]
 	use frame_support::serde::{Deserialize, Serialize};
 
 	type AccountOf<T> = <T as frame_system::Config>::AccountId;
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	/// A Kitty is identified by its collection and its index within the collection.
+	pub type KittyIdOf<T> = (<T as Config>::CollectionId, <T as Config>::KittyIndex);
+	pub type CollectionDetailsOf<T> = CollectionDetails<AccountOf<T>, BalanceOf<T>>;
+	pub type SignedListingOf<T> =
+		SignedListing<KittyIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	/// Domain separator of the message a seller signs to list a Kitty off-chain.
+	pub const LISTING_CONTEXT: &[u8] = b"kitty-listing";
+
+	/// The storage version: 1 moved Kitties into collections, 2 added their birth block and
+	/// life stage.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
@@ -28,6 +84,10 @@ pub mod pallet {
 		pub price: Option<BalanceOf<T>>,
 		pub gender: Gender,
 		pub owner: AccountOf<T>,
+		/// Block the Kitty was minted or bred in.
+		pub born_at: T::BlockNumber,
+		/// Life stage last observed by an action. See `Pallet::life_stage` for the current one.
+		pub stage: LifeStage,
 	}
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -36,6 +96,84 @@ pub mod pallet {
 		Female,
 	}
 
+	/// Life stage of a Kitty, derived from its age.
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum LifeStage {
+		/// Younger than `KittenDuration`.
+		Kitten,
+		/// Up to `AdultDuration` blocks after growing up. Only adults can breed.
+		Adult,
+		/// Older than a kitten and an adult together.
+		Elder,
+		/// Retired by its owner with `retire_kitty`, for the rest of its life.
+		Retired,
+	}
+
+	/// The last sale of a Kitty.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct SaleRecord<AccountId, Balance, BlockNumber> {
+		pub seller: AccountId,
+		pub buyer: AccountId,
+		pub price: Balance,
+		pub block: BlockNumber,
+	}
+
+	/// Terms of a listing beyond its ask price.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ListingTerms<AssetId, BlockNumber> {
+		/// Asset the ask price is denominated in, `None` for the native currency.
+		pub asset: Option<AssetId>,
+		/// Block from which the listing can no longer be bought and is removed.
+		pub expires_at: Option<BlockNumber>,
+	}
+
+	/// A listing signed off-chain by the owner of the Kitty and settled with
+	/// `buy_with_signed_listing`, so listing costs no transaction.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct SignedListing<KittyId, Balance, BlockNumber> {
+		pub kitty_id: KittyId,
+		/// Price in the native currency.
+		pub price: Balance,
+		/// Block from which the listing can no longer be bought.
+		pub expires_at: BlockNumber,
+		/// The Kitty's `OwnershipNonces` entry when the listing was signed. Any change of owner
+		/// since, including a sale with this listing, invalidates it.
+		pub ownership: u32,
+		/// At least the seller's `ListingNonces` entry, or the listing is cancelled.
+		pub nonce: u32,
+	}
+
+	/// Settings of a Kitty collection.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct CollectionDetails<AccountId, Balance> {
+		/// Creator of the collection, the only account that can mint into it and the recipient
+		/// of its royalty. `None` for the default collection, in which anyone can mint.
+		pub owner: Option<AccountId>,
+		/// `CollectionDeposit` reserved from the owner.
+		pub deposit: Balance,
+		/// Whether two Kitties of the collection can be bred.
+		pub breedable: bool,
+		/// The maximum number of Kitties minted or bred in the collection.
+		pub max_supply: Option<u32>,
+		/// Share of every sale price paid to the owner.
+		pub royalty: Perbill,
+	}
+
+	/// Marketplace aggregates of one `StatsPeriod`.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct MarketPeriodStats<Balance> {
+		/// Sum of all sale prices.
+		pub volume: Balance,
+		/// Number of sales.
+		pub sales: u32,
+		/// Lowest ask among the Kitties listed in the native currency, after the last listing,
+		/// delisting or sale of the period. `None` if no Kitty was listed then.
+		pub floor_price: Option<Balance>,
+	}
+
 	// #[pallet::type_value]
 	// pub fn GetDefaultValue() -> KittyIndex {
 	// 	// 定义结构的默认值获取方式
@@ -55,6 +193,9 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxKittyOwned: Get<u32>;
 
+		/// Per-account ownership limit, capped at `MaxKittyOwned`.
+		type KittyQuota: crate::KittyQuota<Self::AccountId>;
+
 		/// 引入资产类型，以便支持质押
 		/// 参考：substrate/frame/treasury/src/lib.rs中的定义
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
@@ -69,15 +210,75 @@ pub mod pallet {
 		// 以后开发遇到在Runtime中定义无符号整型，可以直接复制套用
 		type KittyIndex: Parameter + AtLeast32BitUnsigned + Default + Copy + Bounded + MaxEncodedLen;
 
+		/// Identifier of a Kitty collection. Collection `0` is the default collection.
+		type CollectionId: Parameter + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
+
+		/// Amount reserved from the creator of a collection.
+		#[pallet::constant]
+		type CollectionDeposit: Get<BalanceOf<Self>>;
+
 		// 定义常量时，必须带上以下宏
 		// 获取Runtime中Kitties pallet定义的质押金额常量
 		// 在创建Kitty前需要做质押，避免反复恶意创建
 		#[pallet::constant]
 		type KittyStake: Get<BalanceOf<Self>>;
+
+		/// The maximum number of Kitties that can ever be minted.
+		#[pallet::constant]
+		type MaxTotalKitties: Get<u32>;
+
+		/// The maximum number of Kitties that can be minted in a single block.
+		#[pallet::constant]
+		type MaxMintsPerBlock: Get<u32>;
+
+		// 铸造价格曲线：price = MintPriceBase + MintPriceStep * LastKittyId
+		/// Price of the first Kitty created with `create_kitty`.
+		#[pallet::constant]
+		type MintPriceBase: Get<BalanceOf<Self>>;
+
+		/// Amount the mint price rises with every Kitty minted.
+		#[pallet::constant]
+		type MintPriceStep: Get<BalanceOf<Self>>;
+
+		/// Account receiving the mint price paid in `create_kitty`.
+		type MintProceedsAccount: Get<Self::AccountId>;
+
+		/// Length in blocks of the periods marketplace statistics are aggregated over.
+		#[pallet::constant]
+		type StatsPeriod: Get<Self::BlockNumber>;
+
+		/// Identifier of the assets Kitties can be priced in besides the native currency.
+		type ListingAssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+		/// The fungible assets used to settle asset-denominated listings.
+		type ListingAssets: fungibles::Transfer<
+			Self::AccountId,
+			AssetId = Self::ListingAssetId,
+			Balance = BalanceOf<Self>,
+		>;
+
+		/// The maximum number of listings that can expire in the same block.
+		#[pallet::constant]
+		type MaxExpiringListings: Get<u32>;
+
+		/// Number of blocks a Kitty stays a kitten after birth.
+		#[pallet::constant]
+		type KittenDuration: Get<Self::BlockNumber>;
+
+		/// Number of blocks a Kitty stays an adult before becoming an elder.
+		#[pallet::constant]
+		type AdultDuration: Get<Self::BlockNumber>;
+
+		/// Signature of off-chain listings, verified against the account of the seller.
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+		/// Public key of `OffchainSignature`, identifying the seller's account.
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_); // ??? 这个是啥意思
 
 	// type KittyIndex = u32; // kitty 标识
@@ -90,16 +291,83 @@ pub mod pallet {
 		// KittyTransferred(T::AccountId, T::AccountId, KittyIndex),
 
 		// ACTION #3: Declare events
-		/// A new Kitty was successfully created. \[sender, kitty_id\]
-		Created(T::AccountId, T::KittyIndex),
-		/// Kitty price was successfully set. \[sender, kitty_id, new_price\]
-		PriceSet(T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
-		/// A Kitty was successfully transferred. \[from, to, kitty_id\]
-		Transferred(T::AccountId, T::AccountId, T::KittyIndex),
-		/// A Kitty was successfully bought. \[buyer, seller, kitty_id, bid_price\]
-		Bought(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
-		/// A Kitty was successfully bred. \[sender, new_kitty_id, parent1, parent2\],
-		BredSuccess(T::AccountId, T::KittyIndex, T::KittyIndex, T::KittyIndex),
+		/// A new Kitty was successfully created.
+		Created {
+			owner: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			dna: [u8; 16],
+			gender: Gender,
+			/// Mint price paid to `MintProceedsAccount`.
+			mint_price: BalanceOf<T>,
+			/// Amount reserved from the owner.
+			stake: BalanceOf<T>,
+		},
+		/// Kitty price was successfully set. A `price` of `None` delists the Kitty.
+		PriceSet {
+			owner: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			price: Option<BalanceOf<T>>,
+			previous_price: Option<BalanceOf<T>>,
+			/// Asset the price is denominated in, `None` for the native currency.
+			asset: Option<T::ListingAssetId>,
+			expires_at: Option<T::BlockNumber>,
+		},
+		/// A listing reached its expiry block and the Kitty was delisted.
+		ListingExpired { owner: T::AccountId, kitty_id: KittyIdOf<T> },
+		/// An action observed that a Kitty reached a new life stage, or its owner retired it.
+		StageChanged {
+			owner: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			previous: LifeStage,
+			stage: LifeStage,
+		},
+		/// A new collection was created.
+		CollectionCreated {
+			collection_id: T::CollectionId,
+			owner: T::AccountId,
+			breedable: bool,
+			max_supply: Option<u32>,
+			royalty: Perbill,
+		},
+		/// A seller cancelled all their signed listings with a nonce below `nonce`.
+		SignedListingsCancelled { seller: T::AccountId, nonce: u32 },
+		/// Root set or removed the ownership quota override of an account.
+		QuotaOverrideSet { who: T::AccountId, quota: Option<u32> },
+		/// A Kitty changed owner, either by a plain transfer or by a sale.
+		Transferred {
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			/// Stake unreserved from `from` and reserved from `to`.
+			stake_moved: BalanceOf<T>,
+			/// Price paid if the transfer was a sale, `None` for a plain transfer.
+			sale_price: Option<BalanceOf<T>>,
+		},
+		/// A Kitty was successfully bought. Always preceded by a `Transferred` event.
+		Bought {
+			buyer: T::AccountId,
+			seller: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			/// Amount paid by the buyer.
+			price: BalanceOf<T>,
+			/// The seller's ask price at the time of the sale.
+			ask_price: BalanceOf<T>,
+			/// Asset the price was paid in, `None` for the native currency.
+			asset: Option<T::ListingAssetId>,
+			/// Part of `price` paid to the collection owner instead of the seller.
+			royalty: BalanceOf<T>,
+			stake_moved: BalanceOf<T>,
+		},
+		/// A Kitty was successfully bred from two parents of the same collection owned by `owner`.
+		Bred {
+			owner: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			parents: (KittyIdOf<T>, KittyIdOf<T>),
+			dna: [u8; 16],
+			gender: Gender,
+			/// Amount reserved from the owner.
+			stake: BalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -110,7 +378,7 @@ pub mod pallet {
 		// ACTION #5a: Declare errors.
 		/// Handles arithmetic overflow when incrementing the Kitty counter.
 		KittyIndexOverflow,
-		/// An account cannot own more Kitties than `MaxKittyCount`.
+		/// An account cannot own more Kitties than its quota.
 		ExceedMaxKittyOwned,
 		/// Buyer cannot be the owner.
 		BuyerIsKittyOwner,
@@ -128,6 +396,43 @@ pub mod pallet {
 		KittyBidPriceTooLow,
 		/// Ensures that an account has enough funds to purchase a Kitty.
 		NotEnoughBalance,
+		/// The Kitty is locked by another pallet and cannot be moved or listed.
+		KittyLocked,
+		/// No more Kitties can be minted than `MaxTotalKitties`.
+		ExceedMaxTotalKitties,
+		/// No more Kitties can be minted in this block than `MaxMintsPerBlock`.
+		ExceedMaxMintsPerBlock,
+		/// The listing has expired and can no longer be bought.
+		ListingExpired,
+		/// A listing's expiry block must be in the future.
+		InvalidListingExpiry,
+		/// No more listings can expire in the requested block than `MaxExpiringListings`.
+		TooManyExpiringListings,
+		/// Handles checking whether the collection exists.
+		CollectionNotExist,
+		/// Only the collection owner can mint into a collection.
+		NotCollectionOwner,
+		/// No more Kitties can be minted in the collection than its `max_supply`.
+		CollectionFull,
+		/// The collection does not allow breeding.
+		BreedingNotAllowed,
+		/// Kitties of different collections cannot be bred.
+		CrossCollectionBreeding,
+		/// Handles arithmetic overflow when incrementing the collection counter.
+		CollectionIdOverflow,
+		/// Only adult Kitties can breed.
+		NotAdult,
+		/// Only adult and elder Kitties can be retired.
+		CannotRetire,
+		/// The signature of a signed listing is not the Kitty owner's.
+		InvalidSignature,
+		/// The signed listing was cancelled by its seller, or the Kitty changed owner since it
+		/// was signed.
+		ListingCancelled,
+		/// Handles arithmetic overflow when cancelling signed listings.
+		ListingNonceOverflow,
+		/// Handles arithmetic overflow when adding the stake to the price of a Kitty.
+		PriceOverflow,
 	} //ValueQuery ???
 
 	// #[pallet::storage]
@@ -146,13 +451,36 @@ pub mod pallet {
 	#[pallet::storage]
 	// getter声明外部 要查询存储时，可以调用get_kitty_id方法，方法名称可自定义。
 	#[pallet::getter(fn last_kitty_id)]
-	/// 存储kitty最新的id，用作索引，也可以用作kitty数量总计(+1)
+	/// 所有集合中已铸造的kitty总数，用于铸造价格和总量限制
 	pub type LastKittyId<T: Config> = StorageValue<_, T::KittyIndex, ValueQuery>; // KittyIndex移到Runtime后，KittyIndex改为T::KittyIndex
 
+	#[pallet::type_value]
+	pub fn FirstCollectionId<T: Config>() -> T::CollectionId {
+		One::one()
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_collection_id)]
+	/// Id of the next collection created with `create_collection`.
+	pub type NextCollectionId<T: Config> =
+		StorageValue<_, T::CollectionId, ValueQuery, FirstCollectionId<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn collections)]
+	/// Collections created with `create_collection`. The default collection has no entry.
+	pub type Collections<T: Config> =
+		StorageMap<_, Twox64Concat, T::CollectionId, CollectionDetailsOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn collection_supply)]
+	/// Number of Kitties minted in a collection, which is also the index of the next one.
+	pub type CollectionSupply<T: Config> =
+		StorageMap<_, Twox64Concat, T::CollectionId, T::KittyIndex, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn kitties)]
 	/// Stores a Kitty's unique traits, owner and price.
-	pub(super) type Kitties<T: Config> = StorageMap<_, Twox64Concat, T::KittyIndex, Kitty<T>>;
+	pub(super) type Kitties<T: Config> = StorageMap<_, Twox64Concat, KittyIdOf<T>, Kitty<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn kitties_owned)]
@@ -161,16 +489,166 @@ pub mod pallet {
 		_,
 		Twox64Concat,
 		T::AccountId,
-		BoundedVec<T::KittyIndex, T::MaxKittyOwned>,
+		BoundedVec<KittyIdOf<T>, T::MaxKittyOwned>,
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn last_sales)]
+	/// The last sale of every Kitty that was ever bought.
+	pub type LastSales<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		KittyIdOf<T>,
+		SaleRecord<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn market_stats)]
+	/// Marketplace aggregates keyed by period index (`block_number / StatsPeriod`).
+	pub type MarketStats<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, MarketPeriodStats<BalanceOf<T>>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn mints_in_block)]
+	/// Number of Kitties minted in the given block, reset lazily on the first mint of a new block.
+	pub(super) type MintsInBlock<T: Config> = StorageValue<_, (T::BlockNumber, u32), ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn locked_kitties)]
+	/// Kitties locked by other pallets (e.g. staking). A locked Kitty cannot be transferred,
+	/// sold or listed until it is unlocked again.
+	pub(super) type LockedKitties<T: Config> = StorageMap<_, Twox64Concat, KittyIdOf<T>, ()>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn listings)]
+	/// Asset and expiry of listed Kitties. Kitties listed in the native currency without an
+	/// expiry have no entry.
+	pub type Listings<T: Config> =
+		StorageMap<_, Twox64Concat, KittyIdOf<T>, ListingTerms<T::ListingAssetId, T::BlockNumber>>;
+
+	#[pallet::storage]
+	/// The ask of every Kitty listed in the native currency, keyed by `ask_key(price)` so that
+	/// iterating starts from the lowest ask.
+	pub(super) type NativeAsks<T: Config> =
+		StorageDoubleMap<_, Identity, [u8; 16], Twox64Concat, KittyIdOf<T>, BalanceOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn expiring_listings)]
+	/// Kitties whose listing expires at the given block, until it is relisted, delisted, sold
+	/// or expires.
+	pub(super) type ExpiringListings<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<KittyIdOf<T>, T::MaxExpiringListings>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn expiry_cursor)]
+	/// The next block whose expiring listings have not been processed yet.
+	pub(super) type ExpiryCursor<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn quota_overrides)]
+	/// Ownership quotas granted by root, replacing `KittyQuota` for the account.
+	pub type QuotaOverrides<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn listing_nonces)]
+	/// The lowest nonce a signed listing of the account must have to be bought.
+	pub type ListingNonces<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn ownership_nonces)]
+	/// Number of times each Kitty changed owner. Signed listings name the current value, so
+	/// they cannot be replayed once the Kitty was sold, even if the seller owns it again.
+	pub type OwnershipNonces<T: Config> =
+		StorageMap<_, Twox64Concat, KittyIdOf<T>, u32, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 区块空闲时下架过期的挂单
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::expire_listings(n, remaining_weight)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+				.saturating_add(crate::migrations::v2::migrate::<T>())
+		}
+
+		// 升级前后检查存储的一致性（当前版本的FRAME还没有try_state钩子）
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			// 迁移前的存储布局无法按当前类型检查
+			if Self::on_chain_storage_version() < STORAGE_VERSION {
+				return Ok(());
+			}
+			Self::do_try_state()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Create a collection owned by the caller, reserving `CollectionDeposit`. Only the
+		/// owner can mint into it, and `royalty` of every sale goes to the owner.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 2))]
+		pub fn create_collection(
+			origin: OriginFor<T>,
+			breedable: bool,
+			max_supply: Option<u32>,
+			royalty: Perbill,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			let collection_id = Self::next_collection_id();
+			let next_id =
+				collection_id.checked_add(&One::one()).ok_or(Error::<T>::CollectionIdOverflow)?;
+			let deposit = T::CollectionDeposit::get();
+			T::Currency::reserve(&owner, deposit).map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			<Collections<T>>::insert(
+				collection_id,
+				CollectionDetails {
+					owner: Some(owner.clone()),
+					deposit,
+					breedable,
+					max_supply,
+					royalty,
+				},
+			);
+			<NextCollectionId<T>>::put(next_id);
+
+			Self::deposit_event(Event::CollectionCreated {
+				collection_id,
+				owner,
+				breedable,
+				max_supply,
+				royalty,
+			});
+			Ok(())
+		}
+
+		/// Mint a Kitty into `collection_id`. Anyone can mint into the default collection `0`.
 		#[pallet::weight(100)]
-		pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
+		pub fn create_kitty(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+		) -> DispatchResult {
 			// ACTION #1: create_kitty
 			let sender = ensure_signed(origin)?;
+			let collection =
+				Self::collection(collection_id).ok_or(Error::<T>::CollectionNotExist)?;
+			if let Some(owner) = collection.owner {
+				ensure!(owner == sender, Error::<T>::NotCollectionOwner);
+			}
 
 			// 获取需要质押的金额
 			let stake_amount = T::KittyStake::get();
@@ -179,12 +657,32 @@ pub mod pallet {
 			T::Currency::reserve(&sender, stake_amount)
 				.map_err(|_| Error::<T>::NotEnoughBalance)?;
 
-			let kitty_id = Self::mint(&sender, None, None)?;
+			// 支付铸造费用（不退还），转入配置的收款账户
+			let mint_price = Self::mint_price();
+			if !mint_price.is_zero() {
+				T::Currency::transfer(
+					&sender,
+					&T::MintProceedsAccount::get(),
+					mint_price,
+					ExistenceRequirement::KeepAlive,
+				)
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			}
+
+			let kitty_id = Self::mint(&sender, collection_id, None, None)?;
 			// Logging to the console
 			log::info!("A kitty is born with ID: {:?}", kitty_id);
 
 			// ACTION #4: Deposit `Created` event
-			Self::deposit_event(Event::Created(sender, kitty_id));
+			let kitty = Self::get_kitty(kitty_id)?;
+			Self::deposit_event(Event::Created {
+				owner: sender,
+				kitty_id,
+				dna: kitty.dna,
+				gender: kitty.gender,
+				mint_price,
+				stake: stake_amount,
+			});
 			// let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
 
 			// let dna = Self::random_value(&who);
@@ -199,33 +697,36 @@ pub mod pallet {
 		}
 
 		// TODO Part IV: set_price
+		/// Set or clear the native ask price of a Kitty. The listing never expires.
 		#[pallet::weight(100)] // 10_1000
 		pub fn set_price(
 			origin: OriginFor<T>,
-			kitty_id: T::KittyIndex,
+			kitty_id: KittyIdOf<T>,
 			new_price: Option<BalanceOf<T>>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::do_set_price(sender, kitty_id, new_price, None, None)
+		}
 
-			// ACTION #1a: Checking Kitty owner
-			ensure!(Self::is_kitty_owner(kitty_id, &sender)?, <Error<T>>::NotKittyOwner);
-			let mut kitty = Self::kitties(kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
-
-			// ACTION #2: Set the Kitty price and update new Kitty infomation to storage.
-			kitty.price = new_price.clone();
-			<Kitties<T>>::insert(kitty_id, kitty);
-
-			// ACTION #3: Deposit a "PriceSet" event.
-			Self::deposit_event(Event::PriceSet(sender, kitty_id, new_price));
-
-			Ok(())
+		/// List a Kitty for sale, optionally priced in a `ListingAssets` asset and with an expiry
+		/// block from which it is delisted automatically.
+		#[pallet::weight(100)]
+		pub fn list_kitty(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			price: BalanceOf<T>,
+			asset: Option<T::ListingAssetId>,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_set_price(sender, kitty_id, Some(price), asset, expires_at)
 		}
 
 		#[pallet::weight(1000)] // 10_1000
 		pub fn transfer(
 			origin: OriginFor<T>,
 			to: T::AccountId,
-			kitty_id: T::KittyIndex,
+			kitty_id: KittyIdOf<T>,
 		) -> DispatchResult {
 			let from = ensure_signed(origin)?;
 
@@ -234,23 +735,16 @@ pub mod pallet {
 
 			// Verify the kitty is not transferring back to its owner.
 			ensure!(from != to, <Error<T>>::TransferToSelf);
+			ensure!(!Self::is_locked(kitty_id), <Error<T>>::KittyLocked);
 
-			// Verify the the recipient has the capacity to receive one more kitty.
-			let to_owned = <KittiesOwned<T>>::get(&to);
-			ensure!(
-				(to_owned.len() as u32) < T::MaxKittyOwned::get(),
-				<Error<T>>::ExceedMaxKittyOwned
-			);
-			// 获取需要质押的金额
-			let stake_amount = T::KittyStake::get();
-
-			// 新的Owner账户进行质押
-			T::Currency::reserve(&to, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
-			// 旧的Owner账户解除质押
-			T::Currency::unreserve(&from, stake_amount);
-
-			Self::transfer_kitty_to(kitty_id, &to)?;
-			Self::deposit_event(Event::Transferred(from, to, kitty_id));
+			Self::transfer_with_stake(kitty_id, &to)?;
+			Self::deposit_event(Event::Transferred {
+				from,
+				to,
+				kitty_id,
+				stake_moved: T::KittyStake::get(),
+				sale_price: None,
+			});
 
 			Ok(())
 		}
@@ -258,7 +752,7 @@ pub mod pallet {
 		#[pallet::weight(100)] // 10_1000
 		pub fn buy_kitty(
 			origin: OriginFor<T>,
-			kitty_id: T::KittyIndex,
+			kitty_id: KittyIdOf<T>,
 			bid_price: BalanceOf<T>,
 		) -> DispatchResult {
 			let buyer = ensure_signed(origin)?;
@@ -266,52 +760,93 @@ pub mod pallet {
 			// Check the kitty exists and buyer is not the current kitty owner
 			let kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
 			ensure!(kitty.owner != buyer, <Error<T>>::BuyerIsKittyOwner);
+			ensure!(!Self::is_locked(kitty_id), <Error<T>>::KittyLocked);
 
 			// ACTION #7: Check if buyer can receive Kitty.
 			// Verify the buyer has the capacity to receive one more kitty
-			let to_owned = <KittiesOwned<T>>::get(&buyer);
-			ensure!(
-				(to_owned.len() as u32) < T::MaxKittyOwned::get(),
-				<Error<T>>::ExceedMaxKittyOwned
-			);
+			Self::ensure_can_own_more(&buyer)?;
 			let seller = kitty.owner.clone();
 			// ACTION #6: Check if the Kitty is for sale.
 			// Check the kitty is for sale and the kitty ask price <= bid_price
-			if let Some(ask_price) = kitty.price {
-				ensure!(ask_price <= bid_price, <Error<T>>::KittyBidPriceTooLow);
-			} else {
-				Err(<Error<T>>::KittyNotForSale)?;
+			let ask_price = kitty.price.ok_or(<Error<T>>::KittyNotForSale)?;
+			ensure!(ask_price <= bid_price, <Error<T>>::KittyBidPriceTooLow);
+			let terms = Self::listings(kitty_id);
+			let asset = terms.as_ref().and_then(|terms| terms.asset);
+			if let Some(expires_at) = terms.and_then(|terms| terms.expires_at) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				ensure!(now < expires_at, <Error<T>>::ListingExpired);
 			}
-			// 获取需要质押的金额配置
-			let stake_amount = T::KittyStake::get();
-			// 检查买家的余额是否足够用于购买和质押
-			let buyer_balance = T::Currency::free_balance(&buyer);
-			ensure!(buyer_balance > (bid_price + stake_amount), <Error<T>>::NotEnoughBalance);
+			Self::settle_sale(kitty_id, seller, buyer, ask_price, bid_price, asset)
+		}
 
-			// 买家质押
-			T::Currency::reserve(&buyer, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
-			// 卖家解除质押
-			T::Currency::unreserve(&seller, stake_amount);
-			// Check the buyer has enough free balance
-			ensure!(T::Currency::free_balance(&buyer) >= bid_price, <Error<T>>::NotEnoughBalance);
+		/// Buy a Kitty listed off-chain: `signature` is the owner's signature of
+		/// `listing_message(listing)`. The buyer pays exactly the listed price.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(12, 11))]
+		pub fn buy_with_signed_listing(
+			origin: OriginFor<T>,
+			listing: SignedListingOf<T>,
+			signature: T::OffchainSignature,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			let kitty_id = listing.kitty_id;
 
-			// ACTION #8: Update Balances using the Currency trait.
-			// Transfer the amount from buyer to seller
-			T::Currency::transfer(&buyer, &seller, bid_price, ExistenceRequirement::KeepAlive)?;
-			// Transfer the kitty from seller to buyer
-			Self::transfer_kitty_to(kitty_id, &buyer)?;
-			// Deposit relevant Event
-			Self::deposit_event(Event::Bought(buyer, seller, kitty_id, bid_price));
+			let kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+			let seller = kitty.owner;
+			ensure!(seller != buyer, <Error<T>>::BuyerIsKittyOwner);
+			ensure!(!Self::is_locked(kitty_id), <Error<T>>::KittyLocked);
+			Self::ensure_can_own_more(&buyer)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now < listing.expires_at, <Error<T>>::ListingExpired);
+			// 低于卖家当前nonce的挂单已被取消；kitty换过主人后，之前签名的挂单全部失效
+			ensure!(listing.nonce >= Self::listing_nonces(&seller), <Error<T>>::ListingCancelled);
+			ensure!(
+				listing.ownership == Self::ownership_nonces(kitty_id),
+				<Error<T>>::ListingCancelled
+			);
+			let message = Self::listing_message(&listing);
+			ensure!(signature.verify(&message[..], &seller), <Error<T>>::InvalidSignature);
+
+			Self::settle_sale(kitty_id, seller, buyer, listing.price, listing.price, None)
+		}
+
+		/// Cancel all outstanding signed listings of the caller by raising the nonce new
+		/// listings must have to the one emitted in `SignedListingsCancelled`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn cancel_signed_listings(origin: OriginFor<T>) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+
+			let nonce = <ListingNonces<T>>::try_mutate(&seller, |nonce| {
+				*nonce = nonce.checked_add(1).ok_or(<Error<T>>::ListingNonceOverflow)?;
+				Ok::<_, Error<T>>(*nonce)
+			})?;
+
+			Self::deposit_event(Event::SignedListingsCancelled { seller, nonce });
 			Ok(())
 		}
 
 		#[pallet::weight(100)] // 10_1000
 		pub fn breed_kitty(
 			origin: OriginFor<T>,
-			parent1: T::KittyIndex,
-			parent2: T::KittyIndex,
+			parent1: KittyIdOf<T>,
+			parent2: KittyIdOf<T>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			// 只能在允许繁殖的同一集合内繁殖，新kitty属于父母的集合
+			let collection_id = parent1.0;
+			ensure!(parent2.0 == collection_id, <Error<T>>::CrossCollectionBreeding);
+			let collection =
+				Self::collection(collection_id).ok_or(<Error<T>>::CollectionNotExist)?;
+			ensure!(collection.breedable, <Error<T>>::BreedingNotAllowed);
+			// 只有成年的kitty可以繁殖
+			let kitty1 = Self::get_kitty(parent1)?;
+			let kitty2 = Self::get_kitty(parent2)?;
+			ensure!(
+				Self::life_stage(&kitty1) == LifeStage::Adult
+					&& Self::life_stage(&kitty2) == LifeStage::Adult,
+				<Error<T>>::NotAdult
+			);
+
 			// 获取需要质押的金额
 			let stake_amount = T::KittyStake::get();
 
@@ -327,13 +862,70 @@ pub mod pallet {
 			let new_dna = Self::breed_dna(parent1, parent2)?;
 
 			// ACTION #10: Mint new Kitty using new DNA
-			let kitty_id = Self::mint(&sender, Some(new_dna), None)?;
+			let kitty_id = Self::mint(&sender, collection_id, Some(new_dna), None)?;
+			for (parent, mut kitty) in [(parent1, kitty1), (parent2, kitty2)] {
+				if Self::observe_stage(parent, &mut kitty) {
+					<Kitties<T>>::insert(parent, kitty);
+				}
+			}
 
 			// Deposit relevant Event
-			Self::deposit_event(Event::BredSuccess(sender, kitty_id, parent1, parent2));
+			let kitty = Self::get_kitty(kitty_id)?;
+			Self::deposit_event(Event::Bred {
+				owner: sender,
+				kitty_id,
+				parents: (parent1, parent2),
+				dna: kitty.dna,
+				gender: kitty.gender,
+				stake: stake_amount,
+			});
+
+			Ok(())
+		}
+
+		/// Retire an adult or elder Kitty. A retired Kitty stays retired and cannot breed.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn retire_kitty(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut kitty = Self::get_kitty(kitty_id)?;
+			ensure!(kitty.owner == sender, <Error<T>>::NotKittyOwner);
+
+			let previous = Self::life_stage(&kitty);
+			ensure!(
+				matches!(previous, LifeStage::Adult | LifeStage::Elder),
+				<Error<T>>::CannotRetire
+			);
+			kitty.stage = LifeStage::Retired;
+			<Kitties<T>>::insert(kitty_id, kitty);
+
+			Self::deposit_event(Event::StageChanged {
+				owner: sender,
+				kitty_id,
+				previous,
+				stage: LifeStage::Retired,
+			});
+			Ok(())
+		}
+
+		/// Grant `who` an ownership quota replacing `KittyQuota`, or remove the override with
+		/// `None`. The quota is still capped at `MaxKittyOwned`.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_quota_override(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			quota: Option<u32>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
 
+			match quota {
+				Some(quota) => <QuotaOverrides<T>>::insert(&who, quota),
+				None => <QuotaOverrides<T>>::remove(&who),
+			}
+
+			Self::deposit_event(Event::QuotaOverrideSet { who, quota });
 			Ok(())
 		}
+
 		// #[pallet::weight(100)] // 10_1000
 		// pub fn breed(
 		// 	origin: OriginFor<T>,
@@ -387,13 +979,13 @@ pub mod pallet {
 	}
 
 	impl<T: Config> Pallet<T> {
-		// get next id
-		fn get_next_id() -> Result<T::KittyIndex, Error<T>> {
-			let kitty_id = Self::last_kitty_id() + One::one();
-			if kitty_id == T::KittyIndex::max_value() {
+		// get next index in the collection
+		fn get_next_id(collection_id: T::CollectionId) -> Result<T::KittyIndex, Error<T>> {
+			let kitty_index = Self::collection_supply(collection_id) + One::one();
+			if kitty_index == T::KittyIndex::max_value() {
 				return Err(Error::<T>::KittyIndexOverflow);
 			}
-			return Ok(kitty_id);
+			return Ok(kitty_index);
 			// match Self::next_kitty_id() {
 			// 	T::KittyIndex::max_value() => Err(Error::<T>::KittyIndexOverflow),
 			// 	val => Ok(val),
@@ -401,7 +993,7 @@ pub mod pallet {
 		}
 
 		// get kitty via id
-		fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty<T>, Error<T>> {
+		fn get_kitty(kitty_id: KittyIdOf<T>) -> Result<Kitty<T>, Error<T>> {
 			match Self::kitties(kitty_id) {
 				Some(kitty) => Ok(kitty),
 				None => Err(Error::<T>::KittyNotExist),
@@ -429,8 +1021,8 @@ pub mod pallet {
 
 		// Create new DNA with existing DNA
 		pub fn breed_dna(
-			parent1: T::KittyIndex,
-			parent2: T::KittyIndex,
+			parent1: KittyIdOf<T>,
+			parent2: KittyIdOf<T>,
 		) -> Result<[u8; 16], Error<T>> {
 			let dna1 = Self::get_kitty(parent1)?.dna;
 			let dna2 = Self::get_kitty(parent2)?.dna;
@@ -442,22 +1034,59 @@ pub mod pallet {
 			Ok(new_dna)
 		}
 
+		/// Details of a collection. The default collection `0` has no storage entry: anyone can
+		/// mint into it, breeding is allowed and there is no supply cap or royalty.
+		pub fn collection(collection_id: T::CollectionId) -> Option<CollectionDetailsOf<T>> {
+			if collection_id.is_zero() {
+				return Some(CollectionDetails {
+					owner: None,
+					deposit: Zero::zero(),
+					breedable: true,
+					max_supply: None,
+					royalty: Perbill::zero(),
+				});
+			}
+			Self::collections(collection_id)
+		}
+
 		// ACTION #2: Write mint function
-		// Helper to mint a Kitty.
+		// Helper to mint a Kitty into a collection. Does not check the collection owner.
 		pub fn mint(
 			owner: &T::AccountId,
+			collection_id: T::CollectionId,
 			dna: Option<[u8; 16]>,
 			gender: Option<Gender>,
-		) -> Result<T::KittyIndex, Error<T>> {
+		) -> Result<KittyIdOf<T>, Error<T>> {
 			let kitty = Kitty::<T> {
 				dna: dna.unwrap_or_else(Self::gen_dna),
 				price: None,
 				gender: gender.unwrap_or_else(Self::gen_gender),
 				owner: owner.clone(),
+				born_at: <frame_system::Pallet<T>>::block_number(),
+				stage: LifeStage::Kitten,
 			};
-			let kitty_id = Self::last_kitty_id(); // 当前序号， 从0开始
-			let next_id = Self::get_next_id()?; // 递增序号
+			let collection =
+				Self::collection(collection_id).ok_or(Error::<T>::CollectionNotExist)?;
+			let kitty_index = Self::collection_supply(collection_id); // 集合内的当前序号， 从0开始
+			let next_index = Self::get_next_id(collection_id)?; // 递增序号
+			let kitty_id = (collection_id, kitty_index);
 			ensure!(Self::kitties(&kitty_id) == None, Error::<T>::KittyExists);
+			if let Some(max_supply) = collection.max_supply {
+				ensure!(kitty_index < max_supply.into(), Error::<T>::CollectionFull);
+			}
+			let total = Self::last_kitty_id();
+			ensure!(
+				total < T::KittyIndex::from(T::MaxTotalKitties::get()),
+				Error::<T>::ExceedMaxTotalKitties
+			);
+
+			// 限制每个区块的铸造数量
+			let now = <frame_system::Pallet<T>>::block_number();
+			let (block, minted) = Self::mints_in_block();
+			let minted = if block == now { minted } else { 0 };
+			ensure!(minted < T::MaxMintsPerBlock::get(), Error::<T>::ExceedMaxMintsPerBlock);
+
+			Self::ensure_can_own_more(owner)?;
 
 			// ??? try_mutate???
 			// Perform this operation first because as it may fail
@@ -465,12 +1094,450 @@ pub mod pallet {
 				.map_err(|_| Error::<T>::ExceedMaxKittyOwned)?;
 
 			<Kitties<T>>::insert(kitty_id, kitty);
-			<LastKittyId<T>>::put(next_id);
+			<CollectionSupply<T>>::insert(collection_id, next_index);
+			<LastKittyId<T>>::put(total + One::one());
+			<MintsInBlock<T>>::put((now, minted + 1));
 			Ok(kitty_id)
 		}
 
+		fn do_set_price(
+			owner: T::AccountId,
+			kitty_id: KittyIdOf<T>,
+			new_price: Option<BalanceOf<T>>,
+			asset: Option<T::ListingAssetId>,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			// ACTION #1a: Checking Kitty owner
+			ensure!(Self::is_kitty_owner(kitty_id, &owner)?, <Error<T>>::NotKittyOwner);
+			ensure!(!Self::is_locked(kitty_id), <Error<T>>::KittyLocked);
+			let mut kitty = Self::kitties(kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
+
+			// 下架时没有过期区块；重新挂单时先移除旧的过期记录，同一区块只记录一次
+			let expires_at = expires_at.filter(|_| new_price.is_some());
+			if let Some(expires_at) = expires_at {
+				let now = <frame_system::Pallet<T>>::block_number();
+				ensure!(expires_at > now, <Error<T>>::InvalidListingExpiry);
+			}
+			let previous_expiry = Self::listings(kitty_id).and_then(|terms| terms.expires_at);
+			if previous_expiry != expires_at {
+				if let Some(previous_expiry) = previous_expiry {
+					Self::unschedule_listing_expiry(kitty_id, previous_expiry);
+				}
+				if let Some(expires_at) = expires_at {
+					<ExpiringListings<T>>::try_mutate(expires_at, |ids| {
+						if ids.contains(&kitty_id) {
+							return Ok(());
+						}
+						ids.try_push(kitty_id)
+					})
+					.map_err(|_| Error::<T>::TooManyExpiringListings)?;
+				}
+			}
+			Self::observe_stage(kitty_id, &mut kitty);
+
+			// ACTION #2: Set the Kitty price and update new Kitty infomation to storage.
+			let previous_price = core::mem::replace(&mut kitty.price, new_price);
+			<Kitties<T>>::insert(kitty_id, kitty);
+			Self::update_ask(kitty_id, previous_price, new_price.filter(|_| asset.is_none()));
+			if new_price.is_some() && (asset.is_some() || expires_at.is_some()) {
+				<Listings<T>>::insert(kitty_id, ListingTerms { asset, expires_at });
+			} else {
+				<Listings<T>>::remove(kitty_id);
+			}
+			// ACTION #3: Deposit a "PriceSet" event.
+			Self::deposit_event(Event::PriceSet {
+				owner,
+				kitty_id,
+				price: new_price,
+				previous_price,
+				asset,
+				expires_at,
+			});
+			Ok(())
+		}
+
+		// Delist the Kitties whose listing expired up to block `now`, resuming from
+		// `ExpiryCursor` and stopping before `remaining_weight` is exhausted.
+		fn expire_listings(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			let per_kitty = db.reads_writes(4, 4);
+			// Worst case of processing a single block of the queue.
+			let per_block = db
+				.reads_writes(1, 1)
+				.saturating_add(per_kitty.saturating_mul(T::MaxExpiringListings::get().into()));
+
+			// Read and write of the cursor.
+			let mut used = db.reads_writes(1, 1);
+			if remaining_weight < used.saturating_add(per_block) {
+				return 0;
+			}
+
+			// No listing can expire before the block the cursor is first used in.
+			let mut cursor = Self::expiry_cursor();
+			if cursor.is_zero() {
+				cursor = now;
+			}
+			while cursor <= now && used.saturating_add(per_block) <= remaining_weight {
+				let expired = <ExpiringListings<T>>::take(cursor);
+				used = used
+					.saturating_add(db.reads_writes(1, 1))
+					.saturating_add(per_kitty.saturating_mul(expired.len() as Weight));
+				for kitty_id in expired {
+					Self::expire_listing(kitty_id, cursor);
+				}
+				cursor = cursor.saturating_add(One::one());
+			}
+			<ExpiryCursor<T>>::put(cursor);
+			used
+		}
+
+		// Remove the terms of a listing and its entry in `ExpiringListings`.
+		fn remove_listing(kitty_id: KittyIdOf<T>) {
+			if let Some(expires_at) = <Listings<T>>::take(kitty_id).and_then(|t| t.expires_at) {
+				Self::unschedule_listing_expiry(kitty_id, expires_at);
+			}
+		}
+
+		fn unschedule_listing_expiry(kitty_id: KittyIdOf<T>, expires_at: T::BlockNumber) {
+			<ExpiringListings<T>>::mutate_exists(expires_at, |ids| {
+				if let Some(list) = ids {
+					list.retain(|id| *id != kitty_id);
+					if list.is_empty() {
+						*ids = None;
+					}
+				}
+			});
+		}
+
+		fn expire_listing(kitty_id: KittyIdOf<T>, block: T::BlockNumber) {
+			match Self::listings(kitty_id) {
+				Some(terms) if terms.expires_at == Some(block) => (),
+				// Relisted, delisted or sold since.
+				_ => return,
+			}
+			<Listings<T>>::remove(kitty_id);
+			if let Some(mut kitty) = Self::kitties(kitty_id) {
+				Self::update_ask(kitty_id, kitty.price.take(), None);
+				let owner = kitty.owner.clone();
+				<Kitties<T>>::insert(kitty_id, kitty);
+				Self::deposit_event(Event::ListingExpired { owner, kitty_id });
+			}
+		}
+
+		/// Check the storage invariants of the pallet:
+		///
+		/// - `KittiesOwned` and the owners in `Kitties` agree.
+		/// - `CollectionSupply` is one past the highest Kitty index of every collection, and
+		///   `LastKittyId` is the number of Kitties.
+		/// - Every Kitty belongs to an existing collection.
+		/// - Every owner has at least `KittyStake` reserved per Kitty.
+		/// - Locked and listed Kitties exist, and listed Kitties have a price.
+		/// - `NativeAsks` holds exactly the asks of the Kitties listed in the native currency.
+		/// - `ExpiringListings` only holds listings expiring in the block they are queued for.
+		///
+		/// Iterates all Kitties, so it is only meant for tests and try-runtime.
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let mut owned_count = 0usize;
+			for (owner, owned) in <KittiesOwned<T>>::iter() {
+				for kitty_id in owned.iter() {
+					let kitty = Self::kitties(kitty_id).ok_or("KittiesOwned has unknown kitty")?;
+					ensure!(kitty.owner == owner, "KittiesOwned disagrees with Kitty owner");
+				}
+				owned_count += owned.len();
+
+				let stake = T::KittyStake::get().saturating_mul((owned.len() as u32).into());
+				ensure!(
+					T::Currency::reserved_balance(&owner) >= stake,
+					"Owner has less than KittyStake reserved per kitty"
+				);
+			}
+
+			let mut max_index = BTreeMap::new();
+			let mut kitty_count = 0usize;
+			for (kitty_id, kitty) in <Kitties<T>>::iter() {
+				ensure!(
+					<KittiesOwned<T>>::get(&kitty.owner).contains(&kitty_id),
+					"Kitty missing from KittiesOwned of its owner"
+				);
+				ensure!(Self::collection(kitty_id.0).is_some(), "Kitty of unknown collection");
+				let asset = Self::listings(kitty_id).and_then(|terms| terms.asset);
+				if let Some(price) = kitty.price.filter(|_| asset.is_none()) {
+					ensure!(
+						<NativeAsks<T>>::contains_key(Self::ask_key(price), kitty_id),
+						"Native ask missing from NativeAsks"
+					);
+				}
+				let (collection_id, kitty_index) = kitty_id;
+				let max = max_index.entry(collection_id).or_insert(kitty_index);
+				*max = (*max).max(kitty_index);
+				kitty_count += 1;
+			}
+			ensure!(owned_count == kitty_count, "KittiesOwned and Kitties count differ");
+			for (collection_id, max) in max_index {
+				ensure!(
+					Self::collection_supply(collection_id) == max + One::one(),
+					"CollectionSupply is not one past the highest kitty index"
+				);
+			}
+			ensure!(
+				Self::last_kitty_id().saturated_into::<u64>() == kitty_count as u64,
+				"LastKittyId is not the number of kitties"
+			);
+
+			for kitty_id in <LockedKitties<T>>::iter_keys() {
+				ensure!(<Kitties<T>>::contains_key(kitty_id), "Locked kitty does not exist");
+			}
+			for kitty_id in <Listings<T>>::iter_keys() {
+				let kitty = Self::kitties(kitty_id).ok_or("Listed kitty does not exist")?;
+				ensure!(kitty.price.is_some(), "Listed kitty has no price");
+			}
+			for (block, kitty_ids) in <ExpiringListings<T>>::iter() {
+				for kitty_id in kitty_ids {
+					ensure!(
+						Self::listings(kitty_id).and_then(|terms| terms.expires_at) == Some(block),
+						"ExpiringListings has a listing not expiring in its block"
+					);
+				}
+			}
+			for (key, kitty_id, price) in <NativeAsks<T>>::iter() {
+				let kitty = Self::kitties(kitty_id).ok_or("NativeAsks has unknown kitty")?;
+				ensure!(
+					kitty.price == Some(price) && key == Self::ask_key(price),
+					"NativeAsks disagrees with Kitty price"
+				);
+				ensure!(
+					Self::listings(kitty_id).and_then(|terms| terms.asset).is_none(),
+					"NativeAsks has asset-denominated listing"
+				);
+			}
+			Ok(())
+		}
+
+		/// Index of the statistics period containing the current block.
+		pub fn current_period() -> T::BlockNumber {
+			let period = T::StatsPeriod::get().max(One::one());
+			<frame_system::Pallet<T>>::block_number() / period
+		}
+
+		// Pay `amount` in `asset`, or in the native currency for `None`.
+		fn pay(
+			asset: Option<T::ListingAssetId>,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			match asset {
+				Some(asset) => {
+					T::ListingAssets::transfer(asset, from, to, amount, true)?;
+				},
+				None => {
+					T::Currency::transfer(from, to, amount, ExistenceRequirement::KeepAlive)?;
+				},
+			}
+			Ok(())
+		}
+
+		fn record_sale(
+			kitty_id: KittyIdOf<T>,
+			seller: &T::AccountId,
+			buyer: &T::AccountId,
+			price: BalanceOf<T>,
+		) {
+			<LastSales<T>>::insert(
+				kitty_id,
+				SaleRecord {
+					seller: seller.clone(),
+					buyer: buyer.clone(),
+					price,
+					block: <frame_system::Pallet<T>>::block_number(),
+				},
+			);
+			<MarketStats<T>>::mutate(Self::current_period(), |stats| {
+				stats.volume = stats.volume.saturating_add(price);
+				stats.sales = stats.sales.saturating_add(1);
+			});
+		}
+
+		/// Key of an ask in `NativeAsks`: the price as big-endian bytes, which order like the
+		/// prices themselves.
+		pub fn ask_key(price: BalanceOf<T>) -> [u8; 16] {
+			price.saturated_into::<u128>().to_be_bytes()
+		}
+
+		/// Lowest ask among the Kitties listed in the native currency. A listing that expired
+		/// counts until `on_idle` removes it.
+		pub fn floor_price() -> Option<BalanceOf<T>> {
+			<NativeAsks<T>>::iter().next().map(|(_, _, price)| price)
+		}
+
+		/// Marketplace aggregates of `period`, with the current floor price if it is the current
+		/// period.
+		pub fn period_stats(period: T::BlockNumber) -> MarketPeriodStats<BalanceOf<T>> {
+			let mut stats = Self::market_stats(period);
+			if period == Self::current_period() {
+				stats.floor_price = Self::floor_price();
+			}
+			stats
+		}
+
+		// Replace the ask of a Kitty in `NativeAsks`, `previous` being its former price in any
+		// currency and `native` its new one in the native currency, and refresh the floor price
+		// of the current period.
+		fn update_ask(
+			kitty_id: KittyIdOf<T>,
+			previous: Option<BalanceOf<T>>,
+			native: Option<BalanceOf<T>>,
+		) {
+			if let Some(price) = previous {
+				<NativeAsks<T>>::remove(Self::ask_key(price), kitty_id);
+			}
+			if let Some(price) = native {
+				<NativeAsks<T>>::insert(Self::ask_key(price), kitty_id, price);
+			}
+			let floor_price = Self::floor_price();
+			<MarketStats<T>>::mutate(Self::current_period(), |stats| {
+				stats.floor_price = floor_price
+			});
+		}
+
+		/// Life stage of a Kitty at the current block, computed from its birth block.
+		pub fn life_stage(kitty: &Kitty<T>) -> LifeStage {
+			if kitty.stage == LifeStage::Retired {
+				return LifeStage::Retired;
+			}
+			let age = <frame_system::Pallet<T>>::block_number().saturating_sub(kitty.born_at);
+			if age < T::KittenDuration::get() {
+				LifeStage::Kitten
+			} else if age < T::KittenDuration::get().saturating_add(T::AdultDuration::get()) {
+				LifeStage::Adult
+			} else {
+				LifeStage::Elder
+			}
+		}
+
+		// Record the current life stage of a Kitty, emitting `StageChanged` if it differs from
+		// the last observed one. Returns whether it changed; the caller stores the Kitty.
+		fn observe_stage(kitty_id: KittyIdOf<T>, kitty: &mut Kitty<T>) -> bool {
+			let stage = Self::life_stage(kitty);
+			if stage == kitty.stage {
+				return false;
+			}
+			Self::deposit_event(Event::StageChanged {
+				owner: kitty.owner.clone(),
+				kitty_id,
+				previous: kitty.stage,
+				stage,
+			});
+			kitty.stage = stage;
+			true
+		}
+
+		/// The message the owner of a Kitty signs to list it off-chain. It includes the genesis
+		/// hash, so a listing signed for one chain cannot be bought on another.
+		pub fn listing_message(listing: &SignedListingOf<T>) -> Vec<u8> {
+			let genesis_hash = <frame_system::Pallet<T>>::block_hash(T::BlockNumber::zero());
+			(LISTING_CONTEXT, genesis_hash, listing).encode()
+		}
+
+		// Move the stake, pay the seller and the collection royalty and hand the Kitty to the
+		// buyer, once the caller checked the sale is allowed.
+		fn settle_sale(
+			kitty_id: KittyIdOf<T>,
+			seller: T::AccountId,
+			buyer: T::AccountId,
+			ask_price: BalanceOf<T>,
+			bid_price: BalanceOf<T>,
+			asset: Option<T::ListingAssetId>,
+		) -> DispatchResult {
+			// 获取需要质押的金额配置
+			let stake_amount = T::KittyStake::get();
+			// 检查买家的余额是否足够用于购买（原生货币计价时）和质押
+			let native_price = if asset.is_none() { bid_price } else { Zero::zero() };
+			let required =
+				native_price.checked_add(&stake_amount).ok_or(<Error<T>>::PriceOverflow)?;
+			let buyer_balance = T::Currency::free_balance(&buyer);
+			ensure!(buyer_balance > required, <Error<T>>::NotEnoughBalance);
+
+			// 买家质押
+			T::Currency::reserve(&buyer, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			// 卖家解除质押
+			T::Currency::unreserve(&seller, stake_amount);
+
+			// 版税支付给集合的创建者（卖家自己是创建者时不收取）
+			let collection = Self::collection(kitty_id.0).ok_or(<Error<T>>::CollectionNotExist)?;
+			let royalty = match collection.owner {
+				Some(ref owner) if *owner != seller => collection.royalty * bid_price,
+				_ => Zero::zero(),
+			};
+
+			// ACTION #8: Update Balances using the Currency trait.
+			// Transfer the amount from buyer to seller
+			if asset.is_none() {
+				// Check the buyer has enough free balance
+				ensure!(
+					T::Currency::free_balance(&buyer) >= bid_price,
+					<Error<T>>::NotEnoughBalance
+				);
+			}
+			Self::pay(asset, &buyer, &seller, bid_price.saturating_sub(royalty))?;
+			if let Some(owner) = collection.owner.filter(|_| !royalty.is_zero()) {
+				Self::pay(asset, &buyer, &owner, royalty)?;
+			}
+			// Transfer the kitty from seller to buyer
+			Self::transfer_kitty_to(kitty_id, &buyer)?;
+			// 记录成交价格和市场统计（仅限原生货币计价）
+			if asset.is_none() {
+				Self::record_sale(kitty_id, &seller, &buyer, bid_price);
+			}
+			// Deposit relevant Event
+			Self::deposit_event(Event::Transferred {
+				from: seller.clone(),
+				to: buyer.clone(),
+				kitty_id,
+				stake_moved: stake_amount,
+				sale_price: Some(bid_price),
+			});
+			Self::deposit_event(Event::Bought {
+				buyer,
+				seller,
+				kitty_id,
+				price: bid_price,
+				ask_price,
+				asset,
+				royalty,
+				stake_moved: stake_amount,
+			});
+			Ok(())
+		}
+
+		/// Price paid for the next Kitty created with `create_kitty`, rising linearly with the
+		/// number of Kitties minted so far.
+		pub fn mint_price() -> BalanceOf<T> {
+			let minted: BalanceOf<T> =
+				Self::last_kitty_id().saturated_into::<u128>().saturated_into();
+			T::MintPriceStep::get()
+				.saturating_mul(minted)
+				.saturating_add(T::MintPriceBase::get())
+		}
+
+		/// The maximum number of Kitties `who` may own: the root override if any, otherwise
+		/// `KittyQuota`, capped at `MaxKittyOwned`.
+		pub fn kitty_quota(who: &T::AccountId) -> u32 {
+			use crate::KittyQuota;
+
+			Self::quota_overrides(who)
+				.unwrap_or_else(|| T::KittyQuota::max_owned(who))
+				.min(T::MaxKittyOwned::get())
+		}
+
+		fn ensure_can_own_more(who: &T::AccountId) -> Result<(), Error<T>> {
+			let owned = <KittiesOwned<T>>::decode_len(who).unwrap_or_default() as u32;
+			ensure!(owned < Self::kitty_quota(who), <Error<T>>::ExceedMaxKittyOwned);
+			Ok(())
+		}
+
 		pub fn is_kitty_owner(
-			kitty_id: T::KittyIndex,
+			kitty_id: KittyIdOf<T>,
 			acct: &T::AccountId,
 		) -> Result<bool, Error<T>> {
 			match Self::kitties(kitty_id) {
@@ -479,8 +1546,75 @@ pub mod pallet {
 			}
 		}
 
+		/// Transfer a Kitty to `to`, moving the `KittyStake` reservation from the current owner
+		/// to the new one. Does not check locks.
+		pub fn transfer_with_stake(
+			kitty_id: KittyIdOf<T>,
+			to: &T::AccountId,
+		) -> Result<(), Error<T>> {
+			let from = Self::get_kitty(kitty_id)?.owner;
+
+			// Verify the the recipient has the capacity to receive one more kitty.
+			Self::ensure_can_own_more(to)?;
+			// 获取需要质押的金额
+			let stake_amount = T::KittyStake::get();
+
+			// 新的Owner账户进行质押
+			T::Currency::reserve(to, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			// 旧的Owner账户解除质押
+			T::Currency::unreserve(&from, stake_amount);
+
+			Self::transfer_kitty_to(kitty_id, to)
+		}
+
+		/// Transfer a Kitty to `to`, repatriating the current owner's reserved `KittyStake` to `to`
+		/// as reserved balance, so `to` needs no funds of its own. Does not check locks.
+		pub fn transfer_with_reserved_stake(
+			kitty_id: KittyIdOf<T>,
+			to: &T::AccountId,
+		) -> Result<(), Error<T>> {
+			let from = Self::get_kitty(kitty_id)?.owner;
+
+			Self::ensure_can_own_more(to)?;
+
+			// 将旧Owner的质押直接转为新Owner的质押
+			T::Currency::repatriate_reserved(
+				&from,
+				to,
+				T::KittyStake::get(),
+				BalanceStatus::Reserved,
+			)
+			.map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			Self::transfer_kitty_to(kitty_id, to)
+		}
+
+		/// Lock a Kitty so it can no longer be transferred, sold or listed. Any ask price is
+		/// cleared. Fails if the Kitty does not exist or is already locked.
+		pub fn lock_kitty(kitty_id: KittyIdOf<T>) -> Result<(), Error<T>> {
+			let mut kitty = Self::get_kitty(kitty_id)?;
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+
+			if kitty.price.is_some() {
+				Self::update_ask(kitty_id, kitty.price.take(), None);
+				<Kitties<T>>::insert(kitty_id, kitty);
+			}
+			Self::remove_listing(kitty_id);
+			<LockedKitties<T>>::insert(kitty_id, ());
+			Ok(())
+		}
+
+		/// Release a lock taken with `lock_kitty`.
+		pub fn unlock_kitty(kitty_id: KittyIdOf<T>) {
+			<LockedKitties<T>>::remove(kitty_id);
+		}
+
+		pub fn is_locked(kitty_id: KittyIdOf<T>) -> bool {
+			<LockedKitties<T>>::contains_key(kitty_id)
+		}
+
 		pub fn transfer_kitty_to(
-			kitty_id: T::KittyIndex,
+			kitty_id: KittyIdOf<T>,
 			to: &T::AccountId,
 		) -> Result<(), Error<T>> {
 			let mut kitty = Self::kitties(kitty_id).ok_or(<Error<T>>::KittyNotExist)?;
@@ -496,16 +1630,22 @@ pub mod pallet {
 			})
 			.map_err(|_| Error::<T>::KittyNotExist)?;
 
+			Self::observe_stage(kitty_id, &mut kitty);
 			// Update the kitty owner
 			kitty.owner = to.clone();
 
 			// Reset the ask price so the kitty is not for sale until `set_price()` is called
 			// by the current owner.
-			kitty.price = None;
+			if kitty.price.is_some() {
+				Self::update_ask(kitty_id, kitty.price.take(), None);
+			}
+			Self::remove_listing(kitty_id);
 
 			<Kitties<T>>::insert(kitty_id, kitty);
 			<KittiesOwned<T>>::try_mutate(to, |vec| vec.try_push(kitty_id))
 				.map_err(|_| Error::<T>::ExceedMaxKittyOwned)?;
+			// 换主人后之前签名的挂单失效
+			<OwnershipNonces<T>>::mutate(kitty_id, |nonce| *nonce = nonce.wrapping_add(1));
 			Ok(())
 		}
 	}
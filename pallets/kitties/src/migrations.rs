@@ -0,0 +1,127 @@
+//! Storage migrations of the kitties pallet.
+
+/// Layouts replaced by later storage versions.
+mod v0 {
+	use crate::Gender;
+	use codec::{Decode, Encode};
+
+	/// A Kitty before it had a birth block and life stage.
+	#[derive(Encode, Decode)]
+	pub struct OldKitty<AccountId, Balance> {
+		pub dna: [u8; 16],
+		pub price: Option<Balance>,
+		pub gender: Gender,
+		pub owner: AccountId,
+	}
+}
+
+/// Move all Kitties into the default collection `0`, keeping their index.
+pub mod v1 {
+	use crate::*;
+	use frame_support::{
+		migration::{put_storage_value, storage_key_iter},
+		pallet_prelude::*,
+		sp_std::vec::Vec,
+		traits::{GetStorageVersion, PalletInfoAccess},
+		BoundedVec, StorageHasher, Twox64Concat,
+	};
+	use sp_runtime::traits::Zero;
+
+	/// The id a Kitty minted before collections existed has after the migration.
+	pub fn legacy_kitty_id<T: Config>(kitty_index: T::KittyIndex) -> KittyIdOf<T> {
+		(Zero::zero(), kitty_index)
+	}
+
+	// Re-key a `Twox64Concat` map of pallet `pallet` keyed by a Kitty index to the Kitty's id in
+	// the default collection.
+	fn migrate_kitty_keyed_map<T: Config, V: Decode + Encode>(
+		pallet: &[u8],
+		item: &[u8],
+	) -> Weight {
+		// Collect first: the new keys share the prefix of the drained ones.
+		let entries: Vec<(T::KittyIndex, V)> =
+			storage_key_iter::<T::KittyIndex, V, Twox64Concat>(pallet, item)
+				.drain()
+				.collect();
+		for (kitty_index, value) in entries.iter() {
+			let key = Twox64Concat::hash(&legacy_kitty_id::<T>(*kitty_index).encode());
+			put_storage_value(pallet, item, &key, value);
+		}
+		let count = entries.len() as Weight;
+		T::DbWeight::get().reads_writes(count, 2 * count)
+	}
+
+	/// Migrate to storage version 1, if not done yet.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return 0;
+		}
+
+		let name = <Pallet<T> as PalletInfoAccess>::name();
+		let pallet = name.as_bytes();
+		// The Kitties keep their old layout until `v2`.
+		let weight = migrate_kitty_keyed_map::<T, super::v0::OldKitty<T::AccountId, BalanceOf<T>>>(
+			pallet, b"Kitties",
+		);
+
+		let mut translated = 0u64;
+		<KittiesOwned<T>>::translate::<BoundedVec<T::KittyIndex, T::MaxKittyOwned>, _>(
+			|_, owned| {
+				translated += 1;
+				Some(legacy_kitty_ids::<T, _>(owned))
+			},
+		);
+
+		// All existing Kitties were minted in order into what is now the default collection.
+		<CollectionSupply<T>>::insert(T::CollectionId::zero(), Pallet::<T>::last_kitty_id());
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		weight + T::DbWeight::get().reads_writes(translated + 1, translated + 2)
+	}
+
+	fn legacy_kitty_ids<T: Config, S: Get<u32>>(
+		indices: BoundedVec<T::KittyIndex, S>,
+	) -> BoundedVec<KittyIdOf<T>, S> {
+		let ids: Vec<_> = indices.into_iter().map(legacy_kitty_id::<T>).collect();
+		// Cannot fail: the number of entries is unchanged.
+		BoundedVec::try_from(ids).unwrap_or_default()
+	}
+}
+
+/// Give every Kitty a birth block and life stage. Their age is unknown, so they are treated as
+/// having just grown up and can breed right away. Also index the asks of the listed Kitties,
+/// all in the native currency, in `NativeAsks`.
+pub mod v2 {
+	use crate::*;
+	use frame_support::{pallet_prelude::*, traits::GetStorageVersion};
+	use sp_runtime::traits::Saturating;
+
+	/// Migrate to storage version 2, if not done yet. Requires version 1.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 2 {
+			return 0;
+		}
+
+		let born_at =
+			<frame_system::Pallet<T>>::block_number().saturating_sub(T::KittenDuration::get());
+		let mut translated = 0u64;
+		<Kitties<T>>::translate::<super::v0::OldKitty<T::AccountId, BalanceOf<T>>, _>(|id, old| {
+			translated += 1;
+			if let Some(price) = old.price {
+				<NativeAsks<T>>::insert(Pallet::<T>::ask_key(price), id, price);
+				translated += 1;
+			}
+			Some(Kitty {
+				dna: old.dna,
+				price: old.price,
+				gender: old.gender,
+				owner: old.owner,
+				born_at,
+				stage: LifeStage::Adult,
+			})
+		});
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(translated + 2, translated + 1)
+	}
+}
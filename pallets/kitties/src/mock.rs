@@ -1,59 +1,153 @@
-// use crate as pallet_template;
-// use frame_support::traits::{ConstU16, ConstU64};
-// use frame_system as system;
-// use sp_core::H256;
-// use sp_runtime::{
-// 	testing::Header,
-// 	traits::{BlakeTwo256, IdentityLookup},
-// };
-
-// type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-// type Block = frame_system::mocking::MockBlock<Test>;
-
-// // Configure a mock runtime to test the pallet.
-// frame_support::construct_runtime!(
-// 	pub enum Test where
-// 		Block = Block,
-// 		NodeBlock = Block,
-// 		UncheckedExtrinsic = UncheckedExtrinsic,
-// 	{
-// 		System: frame_system,
-// 		TemplateModule: pallet_template,
-// 	}
-// );
-
-// impl system::Config for Test {
-// 	type BaseCallFilter = frame_support::traits::Everything;
-// 	type BlockWeights = ();
-// 	type BlockLength = ();
-// 	type DbWeight = ();
-// 	type Origin = Origin;
-// 	type Call = Call;
-// 	type Index = u64;
-// 	type BlockNumber = u64;
-// 	type Hash = H256;
-// 	type Hashing = BlakeTwo256;
-// 	type AccountId = u64;
-// 	type Lookup = IdentityLookup<Self::AccountId>;
-// 	type Header = Header;
-// 	type Event = Event;
-// 	type BlockHashCount = ConstU64<250>;
-// 	type Version = ();
-// 	type PalletInfo = PalletInfo;
-// 	type AccountData = ();
-// 	type OnNewAccount = ();
-// 	type OnKilledAccount = ();
-// 	type SystemWeightInfo = ();
-// 	type SS58Prefix = ConstU16<42>;
-// 	type OnSetCode = ();
-// 	type MaxConsumers = frame_support::traits::ConstU32<16>;
-// }
-
-// // impl pallet_template::Config for Test {
-// // 	type Event = Event;
-// // }
-
-// // Build genesis storage according to the mock runtime.
-// pub fn new_test_ext() -> sp_io::TestExternalities {
-// 	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
-// }
+use crate as pallet_kitties;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_randomness_collective_flip::Config for Test {}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<1>;
+	type MetadataDepositBase = ConstU64<1>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+/// Account 2 may own a single Kitty, everybody else up to `MaxKittyOwned`.
+pub struct TestQuota;
+
+impl pallet_kitties::KittyQuota<u64> for TestQuota {
+	fn max_owned(who: &u64) -> u32 {
+		if *who == 2 {
+			1
+		} else {
+			u32::MAX
+		}
+	}
+}
+
+pub const STAKE: u64 = 100;
+pub const MINT_PROCEEDS: u64 = 99;
+pub const COLLECTION_DEPOSIT: u64 = 500;
+pub const KITTEN: u64 = 10;
+pub const ADULT: u64 = 20;
+
+impl pallet_kitties::Config for Test {
+	type Event = Event;
+	type KittyRandomness = RandomnessCollectiveFlip;
+	type MaxKittyOwned = ConstU32<3>;
+	type KittyQuota = TestQuota;
+	type Currency = Balances;
+	type KittyIndex = u32;
+	type CollectionId = u32;
+	type CollectionDeposit = ConstU64<COLLECTION_DEPOSIT>;
+	type KittyStake = ConstU64<STAKE>;
+	type MaxTotalKitties = ConstU32<100>;
+	type MaxMintsPerBlock = ConstU32<10>;
+	type MintPriceBase = ConstU64<50>;
+	type MintPriceStep = ConstU64<10>;
+	type MintProceedsAccount = ConstU64<MINT_PROCEEDS>;
+	type StatsPeriod = ConstU64<100>;
+	type ListingAssetId = u32;
+	type ListingAssets = Assets;
+	type MaxExpiringListings = ConstU32<10>;
+	type KittenDuration = ConstU64<KITTEN>;
+	type AdultDuration = ConstU64<ADULT>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10_000), (2, 10_000), (3, 10_000), (MINT_PROCEEDS, 1)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Events deposited by the kitties pallet, in order.
+pub fn kitty_events() -> Vec<pallet_kitties::Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			Event::Kitties(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
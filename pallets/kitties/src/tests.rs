@@ -1,3 +1,824 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations, mock::*, Error, Event as KittiesEvent, ExpiringListings, Gender, KittiesOwned,
+	Kitty, LastKittyId, LifeStage, MarketPeriodStats, SaleRecord, SignedListing, STORAGE_VERSION,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	migration::put_storage_value,
+	traits::{GetStorageVersion, Hooks, ReservableCurrency},
+	StorageHasher, Twox64Concat,
+};
+use sp_runtime::{testing::TestSignature, Perbill};
 
+// 创建kitty：事件携带DNA、性别、铸造价格和质押金额
+#[test]
+fn create_kitty_emits_created() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+
+		let kitty = Kitties::kitties((0, 0)).unwrap();
+		assert_eq!(
+			kitty_events(),
+			vec![KittiesEvent::Created {
+				owner: 1,
+				kitty_id: (0, 0),
+				dna: kitty.dna,
+				gender: kitty.gender,
+				mint_price: 50,
+				stake: STAKE,
+			}]
+		);
+		assert_eq!(Balances::reserved_balance(1), STAKE);
+		assert_eq!(Balances::free_balance(MINT_PROCEEDS), 1 + 50);
+	});
+}
+
+// 铸造价格随已铸造数量线性上涨，铸造费用转入收款账户
+#[test]
+fn mint_price_rises_with_supply() {
+	new_test_ext().execute_with(|| {
+		let minted_at = |price: u64| {
+			matches!(
+				kitty_events().last(),
+				Some(KittiesEvent::Created { mint_price, .. }) if *mint_price == price
+			)
+		};
+
+		assert_eq!(Kitties::mint_price(), 50);
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert!(minted_at(50));
+		assert_eq!(Balances::free_balance(1), 10_000 - 50 - STAKE);
+		assert_eq!(Balances::free_balance(MINT_PROCEEDS), 1 + 50);
+
+		assert_eq!(Kitties::mint_price(), 60);
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		assert!(minted_at(60));
+		assert_eq!(Balances::free_balance(2), 10_000 - 60 - STAKE);
+		assert_eq!(Balances::free_balance(MINT_PROCEEDS), 1 + 50 + 60);
+
+		LastKittyId::<Test>::put(50);
+		assert_eq!(Kitties::mint_price(), 550);
+		assert_ok!(Kitties::create_kitty(Origin::signed(3), 0));
+		assert!(minted_at(550));
+		assert_eq!(Balances::free_balance(3), 10_000 - 550 - STAKE);
+		assert_eq!(Balances::free_balance(MINT_PROCEEDS), 1 + 50 + 60 + 550);
+	});
+}
+
+// 总量达到上限后不能再铸造
+#[test]
+fn total_supply_is_capped() {
+	new_test_ext().execute_with(|| {
+		LastKittyId::<Test>::put(99);
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_eq!(Kitties::last_kitty_id(), 100);
+
+		assert_noop!(
+			Kitties::create_kitty(Origin::signed(3), 0),
+			Error::<Test>::ExceedMaxTotalKitties
+		);
+		assert!(matches!(
+			Kitties::mint(&3, 0, None, None),
+			Err(Error::<Test>::ExceedMaxTotalKitties)
+		));
+	});
+}
+
+// 每个区块的铸造数量有上限，下一个区块可以继续铸造
+#[test]
+fn mints_per_block_are_limited() {
+	new_test_ext().execute_with(|| {
+		for owner in 10..20 {
+			assert_ok!(Kitties::mint(&owner, 0, None, None));
+		}
+		assert_noop!(
+			Kitties::create_kitty(Origin::signed(1), 0),
+			Error::<Test>::ExceedMaxMintsPerBlock
+		);
+
+		System::set_block_number(2);
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert!(Kitties::kitties((0, 10)).is_some());
+	});
+}
+
+// 设置价格：事件携带之前的价格
+#[test]
+fn set_price_emits_previous_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(500)));
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), None));
+
+		assert_eq!(
+			kitty_events()[1..],
+			[
+				KittiesEvent::PriceSet {
+					owner: 1,
+					kitty_id: (0, 0),
+					price: Some(500),
+					previous_price: None,
+					asset: None,
+					expires_at: None,
+				},
+				KittiesEvent::PriceSet {
+					owner: 1,
+					kitty_id: (0, 0),
+					price: None,
+					previous_price: Some(500),
+					asset: None,
+					expires_at: None,
+				},
+			]
+		);
+
+		assert_noop!(
+			Kitties::set_price(Origin::signed(2), (0, 0), Some(1)),
+			Error::<Test>::NotKittyOwner
+		);
+	});
+}
+
+// 转移kitty：质押随之转移，事件不带成交价
+#[test]
+fn transfer_emits_transferred_without_sale_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, (0, 0)));
+
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::Transferred {
+				from: 1,
+				to: 2,
+				kitty_id: (0, 0),
+				stake_moved: STAKE,
+				sale_price: None,
+			})
+		);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), STAKE);
+
+		assert_noop!(Kitties::transfer(Origin::signed(1), 2, (0, 0)), Error::<Test>::NotKittyOwner);
+	});
+}
+
+// 购买kitty：先发Transferred（带成交价），再发Bought
+#[test]
+fn buy_kitty_emits_transferred_and_bought() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_noop!(
+			Kitties::buy_kitty(Origin::signed(2), (0, 0), 500),
+			Error::<Test>::KittyNotForSale
+		);
+
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(500)));
+		assert_noop!(
+			Kitties::buy_kitty(Origin::signed(2), (0, 0), 499),
+			Error::<Test>::KittyBidPriceTooLow
+		);
+		// 出价加上质押溢出时直接报错，而不是回绕成一个很小的金额
+		assert_noop!(
+			Kitties::buy_kitty(Origin::signed(2), (0, 0), u64::MAX),
+			Error::<Test>::PriceOverflow
+		);
+		assert_ok!(Kitties::buy_kitty(Origin::signed(2), (0, 0), 600));
+
+		assert_eq!(
+			kitty_events()[2..],
+			[
+				KittiesEvent::Transferred {
+					from: 1,
+					to: 2,
+					kitty_id: (0, 0),
+					stake_moved: STAKE,
+					sale_price: Some(600),
+				},
+				KittiesEvent::Bought {
+					buyer: 2,
+					seller: 1,
+					kitty_id: (0, 0),
+					price: 600,
+					ask_price: 500,
+					asset: None,
+					royalty: 0,
+					stake_moved: STAKE,
+				},
+			]
+		);
+		assert_eq!(Balances::free_balance(1), 10_000 - 50 + 600);
+		assert_eq!(Balances::reserved_balance(2), STAKE);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 成交记录和市场统计：地板价是当前原生货币挂单中的最低价，统计周期按区块滚动
+#[test]
+fn sales_are_recorded_per_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_eq!(Kitties::current_period(), 0);
+
+		// 低价挂单后下架，地板价随之恢复
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(1)));
+		assert_eq!(Kitties::floor_price(), Some(1));
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), None));
+		assert_eq!(Kitties::floor_price(), None);
+		assert_eq!(Kitties::market_stats(0), MarketPeriodStats::default());
+
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(500)));
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 1), Some(300)));
+		assert_eq!(Kitties::floor_price(), Some(300));
+		// 改价时替换原来的挂单
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 1), Some(400)));
+		assert_eq!(Kitties::floor_price(), Some(400));
+
+		// 成交后挂单不再计入地板价
+		assert_ok!(Kitties::buy_kitty(Origin::signed(3), (0, 1), 400));
+		assert_eq!(Kitties::floor_price(), Some(500));
+		assert_ok!(Kitties::buy_kitty(Origin::signed(2), (0, 0), 600));
+		assert_eq!(
+			Kitties::last_sales((0, 0)),
+			Some(SaleRecord { seller: 1, buyer: 2, price: 600, block: 1 })
+		);
+		assert_eq!(Kitties::floor_price(), None);
+		let first_period = MarketPeriodStats { volume: 1_000, sales: 2, floor_price: None };
+		assert_eq!(Kitties::market_stats(0), first_period);
+		assert_ok!(Kitties::do_try_state());
+
+		// 新周期重新统计，旧周期保持不变；当前周期的地板价始终是当前的最低挂单
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 2), Some(800)));
+		let first_period = MarketPeriodStats { floor_price: Some(800), ..first_period };
+		System::set_block_number(100);
+		assert_eq!(Kitties::current_period(), 1);
+		assert_eq!(
+			Kitties::period_stats(1),
+			MarketPeriodStats { volume: 0, sales: 0, floor_price: Some(800) }
+		);
+		assert_ok!(Kitties::set_price(Origin::signed(2), (0, 0), Some(700)));
+		assert_ok!(Kitties::buy_kitty(Origin::signed(3), (0, 0), 700));
+		assert_eq!(
+			Kitties::last_sales((0, 0)),
+			Some(SaleRecord { seller: 2, buyer: 3, price: 700, block: 100 })
+		);
+		assert_eq!(
+			Kitties::period_stats(1),
+			MarketPeriodStats { volume: 700, sales: 1, floor_price: Some(800) }
+		);
+		assert_eq!(Kitties::period_stats(0), first_period);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+#[test]
+fn breed_kitty_emits_bred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		System::set_block_number(1 + KITTEN);
+		assert_ok!(Kitties::breed_kitty(Origin::signed(1), (0, 0), (0, 1)));
+
+		let kitty = Kitties::kitties((0, 2)).unwrap();
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::Bred {
+				owner: 1,
+				kitty_id: (0, 2),
+				parents: ((0, 0), (0, 1)),
+				dna: kitty.dna,
+				gender: kitty.gender,
+				stake: STAKE,
+			})
+		);
+		assert_eq!(Balances::reserved_balance(1), 3 * STAKE);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 挂单过期：过期后无法购买，on_idle自动下架
+#[test]
+fn expired_listing_is_delisted_on_idle() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_noop!(
+			Kitties::list_kitty(Origin::signed(1), (0, 0), 500, None, Some(1)),
+			Error::<Test>::InvalidListingExpiry
+		);
+		assert_ok!(Kitties::list_kitty(Origin::signed(1), (0, 0), 500, None, Some(5)));
+		assert!(Kitties::listings((0, 0)).is_some());
+
+		// 过期前的区块不会下架
+		Kitties::on_idle(4, u64::MAX);
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().price, Some(500));
+		assert_eq!(Kitties::floor_price(), Some(500));
+
+		System::set_block_number(5);
+		assert_noop!(
+			Kitties::buy_kitty(Origin::signed(2), (0, 0), 500),
+			Error::<Test>::ListingExpired
+		);
+
+		Kitties::on_idle(5, u64::MAX);
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().price, None);
+		assert_eq!(Kitties::listings((0, 0)), None);
+		assert_eq!(Kitties::floor_price(), None);
+		assert_eq!(Kitties::expiry_cursor(), 6);
+		assert_ok!(Kitties::do_try_state());
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::ListingExpired { owner: 1, kitty_id: (0, 0) })
+		);
+	});
+}
+
+// 重新挂单后，旧的过期记录被移除，不再生效
+#[test]
+fn relisting_overrides_previous_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::list_kitty(Origin::signed(1), (0, 0), 500, None, Some(5)));
+		assert_ok!(Kitties::list_kitty(Origin::signed(1), (0, 0), 450, None, Some(6)));
+		assert!(Kitties::expiring_listings(5).is_empty());
+		assert_eq!(Kitties::expiring_listings(6).into_inner(), vec![(0, 0)]);
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(400)));
+		assert!(Kitties::expiring_listings(6).is_empty());
+		assert_ok!(Kitties::do_try_state());
+
+		System::set_block_number(6);
+		Kitties::on_idle(6, u64::MAX);
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().price, Some(400));
+	});
+}
+
+// 反复挂单同一只kitty只占用过期区块的一个位置，下架和成交时释放
+#[test]
+fn relisting_does_not_fill_the_expiry_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		for price in 1..=20 {
+			assert_ok!(Kitties::list_kitty(Origin::signed(1), (0, 0), price, None, Some(5)));
+		}
+		assert_eq!(Kitties::expiring_listings(5).into_inner(), vec![(0, 0)]);
+		assert_ok!(Kitties::list_kitty(Origin::signed(2), (0, 1), 500, None, Some(5)));
+		assert_eq!(Kitties::expiring_listings(5).into_inner(), vec![(0, 0), (0, 1)]);
+
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), None));
+		assert_eq!(Kitties::expiring_listings(5).into_inner(), vec![(0, 1)]);
+		assert_ok!(Kitties::buy_kitty(Origin::signed(3), (0, 1), 500));
+		assert!(!ExpiringListings::<Test>::contains_key(5));
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 以资产计价的挂单通过pallet_assets结算
+#[test]
+fn buy_kitty_priced_in_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 7, 1, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 7, 2, 1_000));
+
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::list_kitty(Origin::signed(1), (0, 0), 300, Some(7), None));
+		// 以资产计价的挂单不计入地板价
+		assert_eq!(Kitties::floor_price(), None);
+		assert_ok!(Kitties::buy_kitty(Origin::signed(2), (0, 0), 300));
+
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().owner, 2);
+		assert_eq!(Kitties::listings((0, 0)), None);
+		assert_eq!(Assets::balance(7, 1), 300);
+		assert_eq!(Assets::balance(7, 2), 700);
+		// 只有质押使用原生货币
+		assert_eq!(Balances::free_balance(2), 10_000 - STAKE);
+		assert_ok!(Kitties::do_try_state());
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::Bought {
+				buyer: 2,
+				seller: 1,
+				kitty_id: (0, 0),
+				price: 300,
+				ask_price: 300,
+				asset: Some(7),
+				royalty: 0,
+				stake_moved: STAKE,
+			})
+		);
+	});
+}
+
+// 存储不一致时检查失败
+#[test]
+fn try_state_detects_drift() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		assert_ok!(Kitties::do_try_state());
+
+		LastKittyId::<Test>::put(5);
+		assert_eq!(Kitties::do_try_state(), Err("LastKittyId is not the number of kitties"));
+		LastKittyId::<Test>::put(2);
+
+		let owned = KittiesOwned::<Test>::take(2);
+		assert_eq!(Kitties::do_try_state(), Err("Kitty missing from KittiesOwned of its owner"));
+		KittiesOwned::<Test>::insert(1, owned);
+		assert_eq!(Kitties::do_try_state(), Err("KittiesOwned disagrees with Kitty owner"));
+	});
+}
+
+// 质押不足时检查失败
+#[test]
+fn try_state_detects_missing_stake() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::do_try_state());
+
+		Balances::unreserve(&1, 1);
+		assert_eq!(
+			Kitties::do_try_state(),
+			Err("Owner has less than KittyStake reserved per kitty")
+		);
+	});
+}
+
+// 账户配额限制铸造、转移和购买，root可以覆盖配额
+#[test]
+fn quota_limits_mint_transfer_and_buy() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		assert_eq!(Kitties::kitty_quota(&2), 1);
+
+		assert_eq!(Kitties::mint(&2, 0, None, None), Err(Error::<Test>::ExceedMaxKittyOwned));
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 2, (0, 0)),
+			Error::<Test>::ExceedMaxKittyOwned
+		);
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(100)));
+		assert_noop!(
+			Kitties::buy_kitty(Origin::signed(2), (0, 0), 100),
+			Error::<Test>::ExceedMaxKittyOwned
+		);
+
+		assert_noop!(
+			Kitties::set_quota_override(Origin::signed(1), 2, Some(2)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Kitties::set_quota_override(Origin::root(), 2, Some(2)));
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::QuotaOverrideSet { who: 2, quota: Some(2) })
+		);
+		assert_ok!(Kitties::buy_kitty(Origin::signed(2), (0, 0), 100));
+
+		// 覆盖值不能超过MaxKittyOwned
+		assert_ok!(Kitties::set_quota_override(Origin::root(), 2, Some(10)));
+		assert_eq!(Kitties::kitty_quota(&2), 3);
+		assert_ok!(Kitties::set_quota_override(Origin::root(), 2, None));
+		assert_eq!(Kitties::kitty_quota(&2), 1);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 集合：只有创建者可以铸造，数量不超过上限
+#[test]
+fn collection_owner_mints_up_to_max_supply() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Kitties::create_kitty(Origin::signed(1), 1),
+			Error::<Test>::CollectionNotExist
+		);
+		assert_ok!(Kitties::create_collection(
+			Origin::signed(1),
+			false,
+			Some(2),
+			Perbill::from_percent(10)
+		));
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::CollectionCreated {
+				collection_id: 1,
+				owner: 1,
+				breedable: false,
+				max_supply: Some(2),
+				royalty: Perbill::from_percent(10),
+			})
+		);
+		assert_eq!(Balances::reserved_balance(1), COLLECTION_DEPOSIT);
+		assert_eq!(Kitties::next_collection_id(), 2);
+
+		assert_noop!(
+			Kitties::create_kitty(Origin::signed(2), 1),
+			Error::<Test>::NotCollectionOwner
+		);
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 1));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 1));
+		assert_eq!(Kitties::kitties_owned(1).into_inner(), vec![(1, 0), (1, 1)]);
+		assert_eq!(Kitties::mint(&1, 1, None, None), Err(Error::<Test>::CollectionFull));
+
+		// 默认集合的序号独立计数
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().owner, 2);
+		assert_eq!(Kitties::collection_supply(1), 2);
+		assert_eq!(Kitties::last_kitty_id(), 3);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 不能跨集合繁殖，也不能在禁止繁殖的集合内繁殖
+#[test]
+fn breeding_is_limited_to_one_breedable_collection() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_collection(Origin::signed(1), false, None, Perbill::zero()));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 1));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 1));
+
+		assert_noop!(
+			Kitties::breed_kitty(Origin::signed(1), (0, 0), (1, 0)),
+			Error::<Test>::CrossCollectionBreeding
+		);
+		assert_noop!(
+			Kitties::breed_kitty(Origin::signed(1), (1, 0), (1, 1)),
+			Error::<Test>::BreedingNotAllowed
+		);
+	});
+}
+
+// 出售集合内的kitty时向集合创建者支付版税
+#[test]
+fn buy_kitty_pays_collection_royalty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_collection(
+			Origin::signed(1),
+			true,
+			None,
+			Perbill::from_percent(10)
+		));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 1));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 3, (1, 0)));
+		assert_ok!(Kitties::set_price(Origin::signed(3), (1, 0), Some(500)));
+		assert_ok!(Kitties::buy_kitty(Origin::signed(2), (1, 0), 500));
+
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::Bought {
+				buyer: 2,
+				seller: 3,
+				kitty_id: (1, 0),
+				price: 500,
+				ask_price: 500,
+				asset: None,
+				royalty: 50,
+				stake_moved: STAKE,
+			})
+		);
+		assert_eq!(Balances::free_balance(1), 10_000 - COLLECTION_DEPOSIT - 50 + 50);
+		assert_eq!(Balances::free_balance(3), 10_000 + 450);
+		assert_eq!(Balances::free_balance(2), 10_000 - 500 - STAKE);
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 迁移：已有的kitty移入默认集合，序号不变，并视为刚成年
+#[test]
+fn migration_moves_kitties_into_default_collection() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(100);
+		// 迁移前的kitty没有出生区块和生命阶段
+		let old_kitty = |dna: [u8; 16], price: Option<u64>| (dna, price, Gender::Male, 1u64);
+		let key = |index: u32| Twox64Concat::hash(&index.encode());
+		put_storage_value(b"Kitties", b"Kitties", &key(0), old_kitty([0; 16], Some(500)));
+		put_storage_value(b"Kitties", b"Kitties", &key(1), old_kitty([1; 16], None));
+		put_storage_value(
+			b"Kitties",
+			b"KittiesOwned",
+			&Twox64Concat::hash(&1u64.encode()),
+			vec![0u32, 1],
+		);
+		LastKittyId::<Test>::put(2);
+		assert_ok!(Balances::reserve(&1, 2 * STAKE));
+
+		migrations::v1::migrate::<Test>();
+		migrations::v2::migrate::<Test>();
+
+		assert_eq!(Kitties::on_chain_storage_version(), STORAGE_VERSION);
+		assert_eq!(
+			Kitties::kitties((0, 1)),
+			Some(Kitty::<Test> {
+				dna: [1; 16],
+				price: None,
+				gender: Gender::Male,
+				owner: 1,
+				born_at: 100 - KITTEN,
+				stage: LifeStage::Adult,
+			})
+		);
+		assert_eq!(Kitties::kitties_owned(1).into_inner(), vec![(0, 0), (0, 1)]);
+		assert_eq!(Kitties::collection_supply(0), 2);
+		// 迁移前的挂单都以原生货币计价，计入地板价
+		assert_eq!(Kitties::floor_price(), Some(500));
+		assert_ok!(Kitties::do_try_state());
+
+		assert_ok!(Kitties::create_kitty(Origin::signed(2), 0));
+		assert_eq!(Kitties::kitties((0, 2)).unwrap().owner, 2);
+		assert_ok!(Kitties::breed_kitty(Origin::signed(1), (0, 0), (0, 1)));
+	});
+}
+
+// 生命阶段按出生区块惰性计算，首次被操作观察到时发出事件
+#[test]
+fn life_stage_follows_birth_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		let stage = |index| Kitties::life_stage(&Kitties::kitties((0, index)).unwrap());
+		assert_eq!(stage(0), LifeStage::Kitten);
+		assert_noop!(
+			Kitties::breed_kitty(Origin::signed(1), (0, 0), (0, 1)),
+			Error::<Test>::NotAdult
+		);
+
+		System::set_block_number(1 + KITTEN);
+		assert_eq!(stage(0), LifeStage::Adult);
+		// 存储的阶段在被操作观察到之前保持不变
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().stage, LifeStage::Kitten);
+		assert_ok!(Kitties::set_price(Origin::signed(1), (0, 0), Some(500)));
+		assert_eq!(
+			kitty_events()[2],
+			KittiesEvent::StageChanged {
+				owner: 1,
+				kitty_id: (0, 0),
+				previous: LifeStage::Kitten,
+				stage: LifeStage::Adult,
+			}
+		);
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().stage, LifeStage::Adult);
+
+		System::set_block_number(1 + KITTEN + ADULT);
+		assert_eq!(stage(0), LifeStage::Elder);
+		assert_noop!(
+			Kitties::breed_kitty(Origin::signed(1), (0, 0), (0, 1)),
+			Error::<Test>::NotAdult
+		);
+	});
+}
+
+// 成年或年老的kitty可以退休，退休后不能繁殖
+#[test]
+fn retire_kitty_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		assert_noop!(Kitties::retire_kitty(Origin::signed(1), (0, 0)), Error::<Test>::CannotRetire);
+
+		System::set_block_number(1 + KITTEN);
+		assert_noop!(
+			Kitties::retire_kitty(Origin::signed(2), (0, 0)),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_ok!(Kitties::retire_kitty(Origin::signed(1), (0, 0)));
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::StageChanged {
+				owner: 1,
+				kitty_id: (0, 0),
+				previous: LifeStage::Adult,
+				stage: LifeStage::Retired,
+			})
+		);
+		assert_noop!(Kitties::retire_kitty(Origin::signed(1), (0, 0)), Error::<Test>::CannotRetire);
+		assert_noop!(
+			Kitties::breed_kitty(Origin::signed(1), (0, 0), (0, 1)),
+			Error::<Test>::NotAdult
+		);
+
+		System::set_block_number(1 + KITTEN + ADULT);
+		assert_eq!(Kitties::life_stage(&Kitties::kitties((0, 0)).unwrap()), LifeStage::Retired);
+	});
+}
+
+// 链下签名挂单：买家提交卖家签名的挂单成交，成交后不能重放
+#[test]
+fn buy_with_signed_listing_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		let listing =
+			SignedListing { kitty_id: (0, 0), price: 500, expires_at: 10, ownership: 0, nonce: 0 };
+		let sign = |who, listing| TestSignature(who, Kitties::listing_message(listing));
+
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), listing.clone(), sign(2, &listing)),
+			Error::<Test>::InvalidSignature
+		);
+		let cheaper = SignedListing { price: 100, ..listing.clone() };
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), cheaper, sign(1, &listing)),
+			Error::<Test>::InvalidSignature
+		);
+		assert_ok!(Kitties::buy_with_signed_listing(
+			Origin::signed(2),
+			listing.clone(),
+			sign(1, &listing)
+		));
+
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::Bought {
+				buyer: 2,
+				seller: 1,
+				kitty_id: (0, 0),
+				price: 500,
+				ask_price: 500,
+				asset: None,
+				royalty: 0,
+				stake_moved: STAKE,
+			})
+		);
+		assert_eq!(Kitties::kitties((0, 0)).unwrap().owner, 2);
+		assert_eq!(Balances::free_balance(1), 10_000 - 50 + 500);
+
+		// 卖家买回后，已成交的挂单不能再次使用
+		assert_ok!(Kitties::transfer(Origin::signed(2), 1, (0, 0)));
+		assert_eq!(Kitties::ownership_nonces((0, 0)), 2);
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(3), listing.clone(), sign(1, &listing)),
+			Error::<Test>::ListingCancelled
+		);
+		let relisted = SignedListing { ownership: 2, ..listing };
+		assert_ok!(Kitties::buy_with_signed_listing(
+			Origin::signed(3),
+			relisted.clone(),
+			sign(1, &relisted)
+		));
+		assert_ok!(Kitties::do_try_state());
+	});
+}
+
+// 卖家转出后再取回kitty，之前签名但未成交的挂单也失效
+#[test]
+fn signed_listings_do_not_survive_a_change_of_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		let listing =
+			SignedListing { kitty_id: (0, 0), price: 500, expires_at: 10, ownership: 0, nonce: 0 };
+		let signature = TestSignature(1, Kitties::listing_message(&listing));
+
+		assert_ok!(Kitties::transfer(Origin::signed(1), 3, (0, 0)));
+		assert_ok!(Kitties::transfer(Origin::signed(3), 1, (0, 0)));
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), listing, signature),
+			Error::<Test>::ListingCancelled
+		);
+	});
+}
+
+// 签名包含创世区块哈希，在其他链上无效
+#[test]
+fn signed_listings_are_bound_to_the_chain() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		let listing =
+			SignedListing { kitty_id: (0, 0), price: 500, expires_at: 10, ownership: 0, nonce: 0 };
+		let signature = TestSignature(1, Kitties::listing_message(&listing));
+
+		frame_system::BlockHash::<Test>::insert(0, sp_core::H256::repeat_byte(1));
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), listing, signature),
+			Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+// 过期或被卖家按nonce取消的签名挂单不能成交
+#[test]
+fn signed_listings_expire_and_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+		let listing =
+			SignedListing { kitty_id: (0, 0), price: 500, expires_at: 10, ownership: 0, nonce: 0 };
+		let signature = TestSignature(1, Kitties::listing_message(&listing));
+
+		System::set_block_number(10);
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), listing.clone(), signature.clone()),
+			Error::<Test>::ListingExpired
+		);
+
+		System::set_block_number(5);
+		assert_ok!(Kitties::cancel_signed_listings(Origin::signed(1)));
+		assert_eq!(
+			kitty_events().last(),
+			Some(&KittiesEvent::SignedListingsCancelled { seller: 1, nonce: 1 })
+		);
+		assert_eq!(Kitties::listing_nonces(1), 1);
+		assert_noop!(
+			Kitties::buy_with_signed_listing(Origin::signed(2), listing, signature),
+			Error::<Test>::ListingCancelled
+		);
+	});
+}
@@ -0,0 +1,432 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Kitty arena.
+///
+/// Two owners pit their kitties against each other. A challenge is issued by one owner, who
+/// reserves `ChallengeDeposit` until the challenge is cancelled or resolved, and accepted by the
+/// other, which locks both kitties until the battle. The battle is decided by the randomness taken
+/// in the reveal block, `RevealDelay` blocks after accepting, so it was not known when the
+/// challenge was accepted and does not change with the block the battle is resolved in. Battle
+/// stats are derived from the kitties' DNA plus the experience they earned in earlier wins. Every
+/// battle updates the kitties' Elo ratings and the bounded top-N leaderboard.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Randomness, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_kitties::{BalanceOf, KittyIdOf};
+	use sp_runtime::traits::{Hash, Zero};
+	use sp_std::prelude::*;
+
+	type CurrencyOf<T> = <T as pallet_kitties::Config>::Currency;
+
+	pub type ChallengeId = u32;
+
+	/// Rating of a kitty that has not fought yet.
+	pub const INITIAL_RATING: u32 = 1200;
+
+	/// Maximum rating change of a single battle.
+	pub const ELO_K_FACTOR: u32 = 32;
+
+	/// Expected score (in per-mille) of the higher rated kitty for rating differences in steps
+	/// of 25 points, i.e. `1000 / (1 + 10^(-d / 400))` for `d = 0, 25, .., 400`.
+	const EXPECTED_SCORE: [u32; 17] =
+		[500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909];
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct Challenge<T: Config> {
+		pub challenger: T::AccountId,
+		pub challenger_kitty: KittyIdOf<T>,
+		pub opponent_kitty: KittyIdOf<T>,
+		/// Block from which the battle can be resolved, set once the opponent accepts.
+		pub reveal_at: Option<T::BlockNumber>,
+		/// Reserved from the challenger until the challenge is cancelled or resolved.
+		pub deposit: BalanceOf<T>,
+	}
+
+	/// The randomness of a reveal block, shared by the battles revealed in it.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct RevealSeed<T: Config> {
+		/// Taken in `on_initialize` of the reveal block.
+		pub seed: Option<T::Hash>,
+		/// Number of accepted battles revealed in the block and not resolved yet.
+		pub battles: u32,
+	}
+
+	/// Battle stats of a kitty.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct KittyStats {
+		pub attack: u32,
+		pub defense: u32,
+		pub speed: u32,
+		pub level: u32,
+	}
+
+	impl KittyStats {
+		pub fn power(&self) -> u32 {
+			self.attack + self.defense + self.speed + self.level * 10
+		}
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_kitties::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Source of the randomness used to resolve battles.
+		type ArenaRandomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+		/// Number of blocks between accepting a challenge and being able to resolve it. At least
+		/// one: the randomness is taken at the start of the reveal block.
+		#[pallet::constant]
+		type RevealDelay: Get<Self::BlockNumber>;
+
+		/// Amount reserved from the challenger while a challenge is open.
+		#[pallet::constant]
+		type ChallengeDeposit: Get<BalanceOf<Self>>;
+
+		/// Experience a kitty gains for every battle it wins.
+		#[pallet::constant]
+		type ExperiencePerWin: Get<u32>;
+
+		/// Number of kitties kept in the leaderboard.
+		#[pallet::constant]
+		type LeaderboardSize: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::type_value]
+	pub fn DefaultRating() -> u32 {
+		INITIAL_RATING
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_challenge_id)]
+	pub type NextChallengeId<T: Config> = StorageValue<_, ChallengeId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn challenges)]
+	/// Open challenges, removed once resolved or cancelled.
+	pub type Challenges<T: Config> = StorageMap<_, Twox64Concat, ChallengeId, Challenge<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn reveal_seeds)]
+	/// The randomness of the reveal blocks with battles not resolved yet.
+	pub type RevealSeeds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, RevealSeed<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn experience)]
+	/// Experience earned by each kitty in the arena.
+	pub type Experience<T: Config> = StorageMap<_, Twox64Concat, KittyIdOf<T>, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn rating)]
+	/// Elo rating of each kitty.
+	pub type Ratings<T: Config> =
+		StorageMap<_, Twox64Concat, KittyIdOf<T>, u32, ValueQuery, DefaultRating>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn leaderboard)]
+	/// The `LeaderboardSize` highest rated kitties, best first.
+	pub type Leaderboard<T: Config> =
+		StorageValue<_, BoundedVec<(KittyIdOf<T>, u32), T::LeaderboardSize>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A challenge was issued. \[challenge_id, challenger, challenger_kitty, opponent_kitty\]
+		ChallengeIssued(ChallengeId, T::AccountId, KittyIdOf<T>, KittyIdOf<T>),
+		/// A challenge was accepted. \[challenge_id, reveal_at\]
+		ChallengeAccepted(ChallengeId, T::BlockNumber),
+		/// A challenge was withdrawn before being accepted. \[challenge_id\]
+		ChallengeCancelled(ChallengeId),
+		/// A battle was fought. \[challenge_id, winner, loser, winner_rating, loser_rating\]
+		BattleResolved(ChallengeId, KittyIdOf<T>, KittyIdOf<T>, u32, u32),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller does not own the kitty.
+		NotKittyOwner,
+		/// A kitty cannot fight a kitty of the same owner.
+		SameOwner,
+		/// No challenge with the given id.
+		ChallengeNotExist,
+		/// The challenge has already been accepted.
+		AlreadyAccepted,
+		/// The challenge has not been accepted yet.
+		NotAccepted,
+		/// Only the challenger can cancel a challenge.
+		NotChallenger,
+		/// The battle cannot be resolved before `reveal_at`.
+		TooEarly,
+		/// Challenge ids are exhausted.
+		ChallengeIdOverflow,
+		/// The challenger cannot reserve the challenge deposit.
+		NotEnoughBalance,
+		/// The challenger no longer owns the challenging kitty.
+		ChallengerNotOwner,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 揭示区块开始时取随机数，之后结算的时机不再影响战斗结果
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let taken = <RevealSeeds<T>>::mutate(now, |reveal| match reveal {
+				Some(reveal) => {
+					reveal.seed = Some(T::ArenaRandomness::random(b"arena").0);
+					true
+				},
+				None => false,
+			});
+			if taken {
+				T::DbWeight::get().reads_writes(1, 1)
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		fn integrity_test() {
+			// 揭示区块的on_initialize必须在接受挑战之后执行，否则取不到随机数
+			assert!(!T::RevealDelay::get().is_zero(), "`RevealDelay` must be at least one");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Challenge the owner of `opponent_kitty` with one of the caller's kitties, reserving
+		/// `ChallengeDeposit` until the challenge is cancelled or resolved.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 3))]
+		pub fn challenge(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			opponent_kitty: KittyIdOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Self::owner_of(kitty_id)? == who, Error::<T>::NotKittyOwner);
+			ensure!(Self::owner_of(opponent_kitty)? != who, Error::<T>::SameOwner);
+
+			let challenge_id = Self::next_challenge_id();
+			let next_id = challenge_id.checked_add(1).ok_or(Error::<T>::ChallengeIdOverflow)?;
+			let deposit = T::ChallengeDeposit::get();
+			CurrencyOf::<T>::reserve(&who, deposit).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			<Challenges<T>>::insert(
+				challenge_id,
+				Challenge::<T> {
+					challenger: who.clone(),
+					challenger_kitty: kitty_id,
+					opponent_kitty,
+					reveal_at: None,
+					deposit,
+				},
+			);
+			<NextChallengeId<T>>::put(next_id);
+
+			Self::deposit_event(Event::ChallengeIssued(
+				challenge_id,
+				who,
+				kitty_id,
+				opponent_kitty,
+			));
+			Ok(())
+		}
+
+		/// Accept a challenge against one of the caller's kitties, locking both kitties until
+		/// the battle. The battle can be resolved `RevealDelay` blocks later.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(7, 6))]
+		pub fn accept(origin: OriginFor<T>, challenge_id: ChallengeId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut challenge =
+				Self::challenges(challenge_id).ok_or(Error::<T>::ChallengeNotExist)?;
+			ensure!(challenge.reveal_at.is_none(), Error::<T>::AlreadyAccepted);
+			ensure!(Self::owner_of(challenge.opponent_kitty)? == who, Error::<T>::NotKittyOwner);
+			Self::lock_battle(&challenge)?;
+
+			let reveal_at = <frame_system::Pallet<T>>::block_number() + T::RevealDelay::get();
+			Self::schedule_reveal(reveal_at);
+			challenge.reveal_at = Some(reveal_at);
+			<Challenges<T>>::insert(challenge_id, challenge);
+
+			Self::deposit_event(Event::ChallengeAccepted(challenge_id, reveal_at));
+			Ok(())
+		}
+
+		/// Withdraw a challenge that has not been accepted yet, releasing its deposit.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn cancel(origin: OriginFor<T>, challenge_id: ChallengeId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let challenge = Self::challenges(challenge_id).ok_or(Error::<T>::ChallengeNotExist)?;
+			ensure!(challenge.challenger == who, Error::<T>::NotChallenger);
+			ensure!(challenge.reveal_at.is_none(), Error::<T>::AlreadyAccepted);
+			CurrencyOf::<T>::unreserve(&who, challenge.deposit);
+			<Challenges<T>>::remove(challenge_id);
+
+			Self::deposit_event(Event::ChallengeCancelled(challenge_id));
+			Ok(())
+		}
+
+		/// Fight an accepted battle with the randomness of its reveal block, unlocking both
+		/// kitties and releasing the challenger's deposit. Anyone can call this.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(12, 10))]
+		pub fn resolve(origin: OriginFor<T>, challenge_id: ChallengeId) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let challenge = Self::challenges(challenge_id).ok_or(Error::<T>::ChallengeNotExist)?;
+			let reveal_at = challenge.reveal_at.ok_or(Error::<T>::NotAccepted)?;
+			let reveal_seed = Self::reveal_seeds(reveal_at).and_then(|reveal| reveal.seed);
+			let reveal_seed = reveal_seed.ok_or(Error::<T>::TooEarly)?;
+
+			// 同一揭示区块的战斗共用随机数，按挑战id区分
+			let seed = T::Hashing::hash_of(&(reveal_seed, challenge_id));
+			let challenger_stats = Self::stats(challenge.challenger_kitty)?;
+			let opponent_stats = Self::stats(challenge.opponent_kitty)?;
+			let (winner, loser) = if Self::battle(&challenger_stats, &opponent_stats, seed.as_ref())
+			{
+				(challenge.challenger_kitty, challenge.opponent_kitty)
+			} else {
+				(challenge.opponent_kitty, challenge.challenger_kitty)
+			};
+
+			<Experience<T>>::mutate(winner, |xp| {
+				*xp = xp.saturating_add(T::ExperiencePerWin::get())
+			});
+			let (winner_rating, loser_rating) = Self::update_ratings(winner, loser);
+			pallet_kitties::Pallet::<T>::unlock_kitty(challenge.challenger_kitty);
+			pallet_kitties::Pallet::<T>::unlock_kitty(challenge.opponent_kitty);
+			CurrencyOf::<T>::unreserve(&challenge.challenger, challenge.deposit);
+			<RevealSeeds<T>>::mutate_exists(reveal_at, |reveal| {
+				if let Some(r) = reveal {
+					r.battles = r.battles.saturating_sub(1);
+					if r.battles == 0 {
+						*reveal = None;
+					}
+				}
+			});
+			<Challenges<T>>::remove(challenge_id);
+
+			Self::deposit_event(Event::BattleResolved(
+				challenge_id,
+				winner,
+				loser,
+				winner_rating,
+				loser_rating,
+			));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn owner_of(kitty_id: KittyIdOf<T>) -> Result<T::AccountId, DispatchError> {
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			Ok(kitty.owner)
+		}
+
+		// 锁定双方小猫，战斗结束前不能转移或出售；挑战者必须仍然拥有其小猫
+		fn lock_battle(challenge: &Challenge<T>) -> DispatchResult {
+			ensure!(
+				Self::owner_of(challenge.challenger_kitty)? == challenge.challenger,
+				Error::<T>::ChallengerNotOwner
+			);
+			pallet_kitties::Pallet::<T>::lock_kitty(challenge.challenger_kitty)?;
+			if let Err(e) = pallet_kitties::Pallet::<T>::lock_kitty(challenge.opponent_kitty) {
+				pallet_kitties::Pallet::<T>::unlock_kitty(challenge.challenger_kitty);
+				return Err(e.into());
+			}
+			Ok(())
+		}
+
+		// Count a battle revealed in `reveal_at`, whose seed is taken in its `on_initialize`.
+		fn schedule_reveal(reveal_at: T::BlockNumber) {
+			<RevealSeeds<T>>::mutate(reveal_at, |reveal| {
+				reveal.get_or_insert(RevealSeed { seed: None, battles: 0 }).battles += 1
+			});
+		}
+
+		/// Battle stats of a kitty: base stats from its DNA, level from its arena experience.
+		pub fn stats(kitty_id: KittyIdOf<T>) -> Result<KittyStats, DispatchError> {
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			Ok(Self::stats_from_dna(&kitty.dna, Self::experience(kitty_id)))
+		}
+
+		pub fn stats_from_dna(dna: &[u8; 16], experience: u32) -> KittyStats {
+			let pair = |i: usize| dna[i] as u32 + dna[i + 1] as u32;
+			KittyStats {
+				attack: pair(0),
+				defense: pair(2),
+				speed: pair(4),
+				level: experience / 100,
+			}
+		}
+
+		/// Whether the first kitty wins. Each side's power is scaled by a luck factor taken from
+		/// `seed`; ties go to the first kitty on an even third seed byte.
+		pub fn battle(first: &KittyStats, second: &KittyStats, seed: &[u8]) -> bool {
+			let luck = |i: usize| 128 + seed.get(i).copied().unwrap_or_default() as u64;
+			let first_score = first.power() as u64 * luck(0);
+			let second_score = second.power() as u64 * luck(1);
+			match first_score.cmp(&second_score) {
+				core::cmp::Ordering::Greater => true,
+				core::cmp::Ordering::Less => false,
+				core::cmp::Ordering::Equal => luck(2) % 2 == 0,
+			}
+		}
+
+		/// Expected score (per-mille) of a kitty rated `rating` against one rated `other`.
+		pub fn expected_score(rating: u32, other: u32) -> u32 {
+			let step = |d: u32| EXPECTED_SCORE[((d + 12) / 25).min(16) as usize];
+			if rating >= other {
+				step(rating - other)
+			} else {
+				1000 - step(other - rating)
+			}
+		}
+
+		// Move rating points from the loser to the winner and refresh the leaderboard.
+		fn update_ratings(winner: KittyIdOf<T>, loser: KittyIdOf<T>) -> (u32, u32) {
+			let winner_rating = Self::rating(winner);
+			let loser_rating = Self::rating(loser);
+			let expected = Self::expected_score(winner_rating, loser_rating);
+			let delta = (ELO_K_FACTOR * (1000 - expected) + 500) / 1000;
+
+			let winner_rating = winner_rating.saturating_add(delta);
+			let loser_rating = loser_rating.saturating_sub(delta);
+			<Ratings<T>>::insert(winner, winner_rating);
+			<Ratings<T>>::insert(loser, loser_rating);
+
+			<Leaderboard<T>>::mutate(|board| {
+				let mut entries = board.clone().into_inner();
+				entries.retain(|(id, _)| *id != winner && *id != loser);
+				for (kitty_id, rating) in [(winner, winner_rating), (loser, loser_rating)] {
+					let pos =
+						entries.iter().position(|(_, r)| *r < rating).unwrap_or(entries.len());
+					entries.insert(pos, (kitty_id, rating));
+				}
+				entries.truncate(T::LeaderboardSize::get() as usize);
+				// Cannot fail: the entries were truncated to the bound.
+				*board = BoundedVec::try_from(entries).unwrap_or_default();
+			});
+			(winner_rating, loser_rating)
+		}
+	}
+}
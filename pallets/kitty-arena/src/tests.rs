@@ -0,0 +1,178 @@
+use crate::{mock::*, Challenges, Error, Event as ArenaEvent, RevealSeeds, INITIAL_RATING};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use pallet_kitties::Gender;
+
+const STRONG_DNA: [u8; 16] = [0xff; 16];
+const WEAK_DNA: [u8; 16] = [0; 16];
+
+fn mint(owner: u64, dna: [u8; 16]) -> (u32, u32) {
+	Kitties::mint(&owner, 0, Some(dna), Some(Gender::Female)).unwrap()
+}
+
+fn run_to(n: u64) {
+	System::set_block_number(n);
+	KittyArena::on_initialize(n);
+}
+
+#[test]
+fn stats_are_derived_from_dna() {
+	let stats = KittyArena::stats_from_dna(&STRONG_DNA, 250);
+	assert_eq!((stats.attack, stats.defense, stats.speed, stats.level), (510, 510, 510, 2));
+	assert_eq!(stats.power(), 1550);
+	assert_eq!(KittyArena::stats_from_dna(&WEAK_DNA, 0).power(), 0);
+}
+
+#[test]
+fn expected_score_works() {
+	assert_eq!(KittyArena::expected_score(1200, 1200), 500);
+	assert_eq!(KittyArena::expected_score(1300, 1200), 640);
+	assert_eq!(KittyArena::expected_score(1200, 1300), 360);
+	assert_eq!(KittyArena::expected_score(2000, 1200), 909);
+}
+
+// 发起挑战，接受挑战，揭示区块之后结算
+#[test]
+fn battle_works() {
+	new_test_ext().execute_with(|| {
+		let strong = mint(1, STRONG_DNA);
+		let weak = mint(2, WEAK_DNA);
+
+		assert_ok!(KittyArena::challenge(Origin::signed(2), weak, strong));
+		System::assert_last_event(Event::KittyArena(ArenaEvent::ChallengeIssued(
+			0, 2, weak, strong,
+		)));
+		assert_eq!(Balances::reserved_balance(2), 10);
+		assert_noop!(KittyArena::resolve(Origin::signed(3), 0), Error::<Test>::NotAccepted);
+
+		assert_ok!(KittyArena::accept(Origin::signed(1), 0));
+		System::assert_last_event(Event::KittyArena(ArenaEvent::ChallengeAccepted(0, 4)));
+		assert!(Kitties::is_locked(strong));
+		assert!(Kitties::is_locked(weak));
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 3, strong),
+			pallet_kitties::Error::<Test>::KittyLocked
+		);
+		assert_noop!(KittyArena::resolve(Origin::signed(3), 0), Error::<Test>::TooEarly);
+
+		// 随机数在揭示区块开始时才确定
+		System::set_block_number(4);
+		assert_noop!(KittyArena::resolve(Origin::signed(3), 0), Error::<Test>::TooEarly);
+		run_to(4);
+		assert!(KittyArena::reveal_seeds(4).unwrap().seed.is_some());
+		assert_ok!(KittyArena::resolve(Origin::signed(3), 0));
+		let winner_rating = INITIAL_RATING + 16;
+		let loser_rating = INITIAL_RATING - 16;
+		System::assert_last_event(Event::KittyArena(ArenaEvent::BattleResolved(
+			0,
+			strong,
+			weak,
+			winner_rating,
+			loser_rating,
+		)));
+
+		assert_eq!(KittyArena::experience(strong), 100);
+		assert_eq!(KittyArena::experience(weak), 0);
+		assert_eq!(KittyArena::rating(strong), winner_rating);
+		assert_eq!(KittyArena::rating(weak), loser_rating);
+		assert_eq!(
+			KittyArena::leaderboard().into_inner(),
+			vec![(strong, winner_rating), (weak, loser_rating)]
+		);
+		assert_eq!(Challenges::<Test>::get(0), None);
+		assert_eq!(RevealSeeds::<Test>::get(4), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(!Kitties::is_locked(strong));
+		assert!(!Kitties::is_locked(weak));
+	});
+}
+
+// 结果由揭示区块的随机数决定，与结算的区块无关
+#[test]
+fn outcome_is_fixed_at_the_reveal_block() {
+	let resolve_at = |block: u64| {
+		new_test_ext().execute_with(|| {
+			let first = mint(1, STRONG_DNA);
+			let second = mint(2, STRONG_DNA);
+			assert_ok!(KittyArena::challenge(Origin::signed(1), first, second));
+			assert_ok!(KittyArena::accept(Origin::signed(2), 0));
+			for n in 2..=block {
+				// 每个区块的随机数都不同
+				System::set_parent_hash(sp_core::H256::repeat_byte(n as u8));
+				RandomnessCollectiveFlip::on_initialize(n);
+				run_to(n);
+			}
+			assert_ok!(KittyArena::resolve(Origin::signed(3), 0));
+			KittyArena::rating(first)
+		})
+	};
+	let rating = resolve_at(4);
+	assert!((5..12).all(|block| resolve_at(block) == rating));
+}
+
+#[test]
+fn challenge_fails() {
+	new_test_ext().execute_with(|| {
+		let first = mint(1, STRONG_DNA);
+		let second = mint(1, WEAK_DNA);
+		let other = mint(2, WEAK_DNA);
+
+		assert_noop!(
+			KittyArena::challenge(Origin::signed(2), first, other),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittyArena::challenge(Origin::signed(1), first, second),
+			Error::<Test>::SameOwner
+		);
+
+		assert_noop!(
+			KittyArena::challenge(Origin::signed(3), mint(3, WEAK_DNA), other),
+			Error::<Test>::NotEnoughBalance
+		);
+
+		assert_ok!(KittyArena::challenge(Origin::signed(1), first, other));
+		assert_noop!(KittyArena::accept(Origin::signed(1), 0), Error::<Test>::NotKittyOwner);
+		assert_noop!(KittyArena::cancel(Origin::signed(2), 0), Error::<Test>::NotChallenger);
+		assert_ok!(KittyArena::cancel(Origin::signed(1), 0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(KittyArena::accept(Origin::signed(2), 0), Error::<Test>::ChallengeNotExist);
+
+		// 挑战者卖出小猫后不能再接受挑战
+		assert_ok!(KittyArena::challenge(Origin::signed(1), first, other));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, first));
+		assert_noop!(KittyArena::accept(Origin::signed(2), 1), Error::<Test>::ChallengerNotOwner);
+		assert_ok!(KittyArena::cancel(Origin::signed(1), 1));
+	});
+}
+
+// 排行榜只保留评分最高的前N名
+#[test]
+fn leaderboard_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let champion = mint(1, STRONG_DNA);
+		let challengers: Vec<(u32, u32)> = (0..3).map(|_| mint(2, WEAK_DNA)).collect();
+
+		for kitty_id in &challengers {
+			assert_ok!(KittyArena::challenge(Origin::signed(2), *kitty_id, champion));
+		}
+		// 冠军同时只能参加一场战斗
+		assert_ok!(KittyArena::accept(Origin::signed(1), 0));
+		assert_noop!(
+			KittyArena::accept(Origin::signed(1), 1),
+			pallet_kitties::Error::<Test>::KittyLocked
+		);
+		for challenge_id in 0..3 {
+			if challenge_id > 0 {
+				assert_ok!(KittyArena::accept(Origin::signed(1), challenge_id));
+			}
+			let reveal_at = System::block_number() + 3;
+			run_to(reveal_at);
+			assert_ok!(KittyArena::resolve(Origin::signed(3), challenge_id));
+		}
+
+		let board = KittyArena::leaderboard();
+		assert_eq!(board.len(), 3);
+		assert_eq!(board[0].0, champion);
+		assert!(board.windows(2).all(|w| w[0].1 >= w[1].1));
+	});
+}
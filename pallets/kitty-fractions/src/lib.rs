@@ -0,0 +1,349 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Fractional kitty ownership.
+///
+/// An owner locks a kitty into a vault and mints a fixed supply of fungible shares for it. The
+/// shares are regular assets and trade freely. Whoever collects every share can redeem the kitty.
+/// Alternatively anyone can start a buyout auction with a price per share: after `BuyoutPeriod`
+/// the highest bidder receives the kitty, and every remaining holder can swap their shares for
+/// the bid price.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{
+			tokens::{
+				fungibles::{Create, Inspect, Mutate},
+				BalanceStatus,
+			},
+			Currency, ExistenceRequirement, ReservableCurrency,
+		},
+		PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_kitties::{BalanceOf, KittyIdOf};
+	use sp_runtime::traits::{AccountIdConversion, CheckedMul, One, Saturating, Zero};
+
+	type CurrencyOf<T> = <T as pallet_kitties::Config>::Currency;
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum VaultStatus<AccountId, Balance, BlockNumber> {
+		/// Shares trade freely; whoever holds all of them can redeem the kitty.
+		Active,
+		/// A buyout auction is running. `deposit` is reserved from the bidder.
+		Auction {
+			bidder: AccountId,
+			price_per_share: Balance,
+			deposit: Balance,
+			ends_at: BlockNumber,
+		},
+		/// The kitty was bought out; remaining holders can swap shares for `price_per_share`.
+		BoughtOut { price_per_share: Balance },
+	}
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct Vault<T: Config> {
+		/// The account that fractionalized the kitty.
+		pub curator: T::AccountId,
+		pub asset_id: T::AssetId,
+		/// Total number of shares minted for the kitty.
+		pub shares: BalanceOf<T>,
+		pub status: VaultStatus<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_kitties::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Identifier of the share assets.
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+		/// The fungible assets used for kitty shares, denominated like the native currency.
+		type Assets: Create<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self>>
+			+ Mutate<Self::AccountId>;
+
+		/// The vault's id, used for deriving the account administering the share assets and
+		/// holding buyout proceeds.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// How long a buyout auction runs after the first bid.
+		#[pallet::constant]
+		type BuyoutPeriod: Get<Self::BlockNumber>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn vaults)]
+	/// Fractionalized kitties.
+	pub type Vaults<T: Config> = StorageMap<_, Twox64Concat, KittyIdOf<T>, Vault<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A kitty was locked and shares were minted. \[owner, kitty_id, asset_id, shares\]
+		Fractionalized(T::AccountId, KittyIdOf<T>, T::AssetId, BalanceOf<T>),
+		/// All shares were burned and the kitty released. \[who, kitty_id\]
+		Redeemed(T::AccountId, KittyIdOf<T>),
+		/// A buyout bid was placed. \[bidder, kitty_id, price_per_share, ends_at\]
+		BuyoutBid(T::AccountId, KittyIdOf<T>, BalanceOf<T>, T::BlockNumber),
+		/// The buyout auction ended and the kitty went to the bidder.
+		/// \[bidder, kitty_id, price_per_share\]
+		BuyoutSettled(T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+		/// The bidder could not pay for the remaining shares when the auction ended, so the bid
+		/// was dropped and its deposit released. \[bidder, kitty_id\]
+		BuyoutCancelled(T::AccountId, KittyIdOf<T>),
+		/// Shares were swapped for buyout proceeds. \[who, kitty_id, amount\]
+		ProceedsClaimed(T::AccountId, KittyIdOf<T>, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller does not own the kitty.
+		NotKittyOwner,
+		/// The kitty is locked by another pallet.
+		KittyLocked,
+		/// At least one share must be minted.
+		ZeroShares,
+		/// The kitty is not fractionalized.
+		NotFractionalized,
+		/// Redeeming requires holding every share.
+		NotAllShares,
+		/// The caller holds no shares.
+		NoShares,
+		/// The kitty was already bought out.
+		AlreadyBoughtOut,
+		/// There is no buyout auction for the kitty.
+		NoAuction,
+		/// The buyout auction has already ended.
+		AuctionEnded,
+		/// The buyout auction is still running.
+		AuctionNotEnded,
+		/// A bid must be higher than the current one.
+		BidTooLow,
+		/// The kitty has not been bought out.
+		NotBoughtOut,
+		/// The bidder cannot pay for the remaining shares.
+		NotEnoughBalance,
+		/// Arithmetic overflow when pricing shares.
+		Overflow,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Lock a kitty and mint `shares` units of the new asset `asset_id` to its owner.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 5))]
+		pub fn fractionalize(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			asset_id: T::AssetId,
+			shares: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!shares.is_zero(), Error::<T>::ZeroShares);
+
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			ensure!(kitty.owner == who, Error::<T>::NotKittyOwner);
+			pallet_kitties::Pallet::<T>::lock_kitty(kitty_id)
+				.map_err(|_| Error::<T>::KittyLocked)?;
+
+			T::Assets::create(asset_id, Self::account_id(), false, One::one())?;
+			T::Assets::mint_into(asset_id, &who, shares)?;
+			<Vaults<T>>::insert(
+				kitty_id,
+				Vault::<T> { curator: who.clone(), asset_id, shares, status: VaultStatus::Active },
+			);
+
+			Self::deposit_event(Event::Fractionalized(who, kitty_id, asset_id, shares));
+			Ok(())
+		}
+
+		/// Burn every share of a kitty and take ownership of it. A running buyout auction is
+		/// cancelled and its bidder refunded.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 6))]
+		pub fn redeem(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let vault = Self::vaults(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let held = T::Assets::balance(vault.asset_id, &who);
+			ensure!(held == vault.shares, Error::<T>::NotAllShares);
+
+			match vault.status {
+				VaultStatus::Active => {},
+				VaultStatus::Auction { bidder, deposit, .. } => {
+					CurrencyOf::<T>::unreserve(&bidder, deposit);
+				},
+				VaultStatus::BoughtOut { .. } => return Err(Error::<T>::AlreadyBoughtOut.into()),
+			}
+
+			T::Assets::burn_from(vault.asset_id, &who, held)?;
+			Self::release_kitty(kitty_id, &who)?;
+			<Vaults<T>>::remove(kitty_id);
+
+			Self::deposit_event(Event::Redeemed(who, kitty_id));
+			Ok(())
+		}
+
+		/// Bid `price_per_share` for every share not held by the caller. The full price is
+		/// reserved until the bid is outbid or the auction settles.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 3))]
+		pub fn bid(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			price_per_share: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut vault = Self::vaults(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			let ends_at = match vault.status.clone() {
+				VaultStatus::Active => {
+					ensure!(!price_per_share.is_zero(), Error::<T>::BidTooLow);
+					now.saturating_add(T::BuyoutPeriod::get())
+				},
+				VaultStatus::Auction { bidder, price_per_share: current, deposit, ends_at } => {
+					ensure!(now < ends_at, Error::<T>::AuctionEnded);
+					ensure!(price_per_share > current, Error::<T>::BidTooLow);
+					CurrencyOf::<T>::unreserve(&bidder, deposit);
+					ends_at
+				},
+				VaultStatus::BoughtOut { .. } => return Err(Error::<T>::AlreadyBoughtOut.into()),
+			};
+
+			let deposit = Self::buyout_cost(&vault, &who, price_per_share)?;
+			CurrencyOf::<T>::reserve(&who, deposit).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			vault.status =
+				VaultStatus::Auction { bidder: who.clone(), price_per_share, deposit, ends_at };
+			<Vaults<T>>::insert(kitty_id, vault);
+
+			Self::deposit_event(Event::BuyoutBid(who, kitty_id, price_per_share, ends_at));
+			Ok(())
+		}
+
+		/// Finish a buyout auction: the bidder pays for the remaining shares and receives the
+		/// kitty. Anyone can call this once the auction has ended. A bidder that can no longer
+		/// pay loses the bid instead, and the vault takes bids again.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(8, 8))]
+		pub fn settle(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let mut vault = Self::vaults(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let (bidder, price_per_share, deposit, ends_at) = match vault.status.clone() {
+				VaultStatus::Auction { bidder, price_per_share, deposit, ends_at } => {
+					(bidder, price_per_share, deposit, ends_at)
+				},
+				_ => return Err(Error::<T>::NoAuction.into()),
+			};
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= ends_at, Error::<T>::AuctionNotEnded);
+
+			// The bidder may have traded shares since bidding, so price the shares again.
+			let cost = Self::buyout_cost(&vault, &bidder, price_per_share)?;
+			if cost > deposit {
+				// 出价后卖出份额又补不足押金时放弃这次出价，否则拍卖无法结束，kitty永远锁定
+				if CurrencyOf::<T>::reserve(&bidder, cost - deposit).is_err() {
+					CurrencyOf::<T>::unreserve(&bidder, deposit);
+					vault.status = VaultStatus::Active;
+					<Vaults<T>>::insert(kitty_id, vault);
+					Self::deposit_event(Event::BuyoutCancelled(bidder, kitty_id));
+					return Ok(());
+				}
+			} else {
+				CurrencyOf::<T>::unreserve(&bidder, deposit - cost);
+			}
+			CurrencyOf::<T>::repatriate_reserved(
+				&bidder,
+				&Self::account_id(),
+				cost,
+				BalanceStatus::Free,
+			)?;
+
+			let held = T::Assets::balance(vault.asset_id, &bidder);
+			if !held.is_zero() {
+				T::Assets::burn_from(vault.asset_id, &bidder, held)?;
+			}
+			Self::release_kitty(kitty_id, &bidder)?;
+
+			if held == vault.shares {
+				<Vaults<T>>::remove(kitty_id);
+			} else {
+				vault.status = VaultStatus::BoughtOut { price_per_share };
+				<Vaults<T>>::insert(kitty_id, vault);
+			}
+
+			Self::deposit_event(Event::BuyoutSettled(bidder, kitty_id, price_per_share));
+			Ok(())
+		}
+
+		/// Swap all shares of a bought out kitty held by the caller for the buyout price.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 4))]
+		pub fn claim_proceeds(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let vault = Self::vaults(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			let price_per_share = match vault.status {
+				VaultStatus::BoughtOut { price_per_share } => price_per_share,
+				_ => return Err(Error::<T>::NotBoughtOut.into()),
+			};
+			let held = T::Assets::balance(vault.asset_id, &who);
+			ensure!(!held.is_zero(), Error::<T>::NoShares);
+			let amount = price_per_share.checked_mul(&held).ok_or(Error::<T>::Overflow)?;
+
+			T::Assets::burn_from(vault.asset_id, &who, held)?;
+			CurrencyOf::<T>::transfer(
+				&Self::account_id(),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			if T::Assets::total_issuance(vault.asset_id).is_zero() {
+				<Vaults<T>>::remove(kitty_id);
+			}
+
+			Self::deposit_event(Event::ProceedsClaimed(who, kitty_id, amount));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account administering share assets and holding buyout proceeds.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		// Price of the shares `who` does not hold yet.
+		fn buyout_cost(
+			vault: &Vault<T>,
+			who: &T::AccountId,
+			price_per_share: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, Error<T>> {
+			let missing = vault.shares.saturating_sub(T::Assets::balance(vault.asset_id, who));
+			price_per_share.checked_mul(&missing).ok_or(Error::<T>::Overflow)
+		}
+
+		// Unlock the kitty and hand it to `to`.
+		fn release_kitty(kitty_id: KittyIdOf<T>, to: &T::AccountId) -> DispatchResult {
+			pallet_kitties::Pallet::<T>::unlock_kitty(kitty_id);
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			if kitty.owner != *to {
+				pallet_kitties::Pallet::<T>::transfer_with_stake(kitty_id, to)?;
+			}
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,161 @@
+use crate::{mock::*, Error, Event as FractionsEvent, VaultStatus, Vaults};
+use frame_support::{assert_noop, assert_ok};
+use pallet_kitties::Gender;
+
+const SHARES_ASSET: u32 = 7;
+
+fn fractionalized_kitty() -> u32 {
+	let kitty_id = Kitties::mint(&1, 0, None, Some(Gender::Male)).unwrap();
+	assert_ok!(KittyFractions::fractionalize(Origin::signed(1), kitty_id, SHARES_ASSET, 100));
+	kitty_id
+}
+
+#[test]
+fn fractionalize_works() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = fractionalized_kitty();
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::Fractionalized(
+			1,
+			kitty_id,
+			SHARES_ASSET,
+			100,
+		)));
+
+		assert_eq!(Assets::balance(SHARES_ASSET, 1), 100);
+		assert!(Kitties::is_locked(kitty_id));
+		assert_eq!(Vaults::<Test>::get(kitty_id).unwrap().status, VaultStatus::Active);
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 2, kitty_id),
+			pallet_kitties::Error::<Test>::KittyLocked
+		);
+		assert_noop!(
+			KittyFractions::fractionalize(Origin::signed(1), kitty_id, SHARES_ASSET + 1, 100),
+			Error::<Test>::KittyLocked
+		);
+	});
+}
+
+#[test]
+fn fractionalize_fails() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = Kitties::mint(&1, 0, None, None).unwrap();
+		assert_noop!(
+			KittyFractions::fractionalize(Origin::signed(2), kitty_id, SHARES_ASSET, 100),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittyFractions::fractionalize(Origin::signed(1), kitty_id, SHARES_ASSET, 0),
+			Error::<Test>::ZeroShares
+		);
+	});
+}
+
+// 集齐全部份额后可以赎回kitty
+#[test]
+fn redeem_requires_all_shares() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = fractionalized_kitty();
+		assert_ok!(Assets::transfer(Origin::signed(1), SHARES_ASSET, 2, 40));
+		assert_noop!(
+			KittyFractions::redeem(Origin::signed(2), kitty_id),
+			Error::<Test>::NotAllShares
+		);
+
+		assert_ok!(Assets::transfer(Origin::signed(1), SHARES_ASSET, 2, 60));
+		assert_ok!(KittyFractions::redeem(Origin::signed(2), kitty_id));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::Redeemed(2, kitty_id)));
+
+		assert_eq!(Kitties::kitties(kitty_id).unwrap().owner, 2);
+		assert!(!Kitties::is_locked(kitty_id));
+		assert_eq!(Assets::balance(SHARES_ASSET, 2), 0);
+		assert_eq!(Vaults::<Test>::get(kitty_id), None);
+	});
+}
+
+// 买断拍卖：出价最高者获得kitty，其余份额持有人按出价兑付
+#[test]
+fn buyout_works() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = fractionalized_kitty();
+		assert_ok!(Assets::transfer(Origin::signed(1), SHARES_ASSET, 2, 30));
+
+		assert_ok!(KittyFractions::bid(Origin::signed(3), kitty_id, 10));
+		assert_eq!(Balances::reserved_balance(3), 1_000);
+		assert_noop!(
+			KittyFractions::bid(Origin::signed(2), kitty_id, 10),
+			Error::<Test>::BidTooLow
+		);
+
+		// account 2 already holds 30 shares and only pays for the other 70
+		assert_ok!(KittyFractions::bid(Origin::signed(2), kitty_id, 20));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::BuyoutBid(
+			2, kitty_id, 20, 11,
+		)));
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::reserved_balance(2), 1_400);
+		assert_noop!(
+			KittyFractions::settle(Origin::signed(3), kitty_id),
+			Error::<Test>::AuctionNotEnded
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			KittyFractions::bid(Origin::signed(3), kitty_id, 30),
+			Error::<Test>::AuctionEnded
+		);
+		assert_ok!(KittyFractions::settle(Origin::signed(3), kitty_id));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::BuyoutSettled(
+			2, kitty_id, 20,
+		)));
+		assert_eq!(Kitties::kitties(kitty_id).unwrap().owner, 2);
+		assert!(!Kitties::is_locked(kitty_id));
+		assert_eq!(Balances::free_balance(2), 10_000 - 1_400 - 100);
+		assert_eq!(
+			Vaults::<Test>::get(kitty_id).unwrap().status,
+			VaultStatus::BoughtOut { price_per_share: 20 }
+		);
+
+		assert_noop!(
+			KittyFractions::claim_proceeds(Origin::signed(3), kitty_id),
+			Error::<Test>::NoShares
+		);
+		assert_ok!(KittyFractions::claim_proceeds(Origin::signed(1), kitty_id));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::ProceedsClaimed(
+			1, kitty_id, 1_400,
+		)));
+		assert_eq!(Balances::free_balance(1), 10_000 + 1_400);
+		assert_eq!(Vaults::<Test>::get(kitty_id), None);
+	});
+}
+
+// 出价后卖出份额且无法补足押金：结算时放弃出价、退还押金，金库重新接受出价
+#[test]
+fn settle_drops_a_bid_the_bidder_cannot_pay() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = fractionalized_kitty();
+		assert_ok!(Assets::transfer(Origin::signed(1), SHARES_ASSET, 2, 90));
+		assert_ok!(KittyFractions::bid(Origin::signed(2), kitty_id, 20));
+		assert_eq!(Balances::reserved_balance(2), 200);
+
+		assert_ok!(Assets::transfer(Origin::signed(2), SHARES_ASSET, 1, 90));
+		assert_ok!(Balances::transfer(Origin::signed(2), 3, 9_700));
+
+		System::set_block_number(11);
+		assert_ok!(KittyFractions::settle(Origin::signed(1), kitty_id));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::BuyoutCancelled(
+			2, kitty_id,
+		)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 300);
+		assert_eq!(Kitties::kitties(kitty_id).unwrap().owner, 1);
+		assert!(Kitties::is_locked(kitty_id));
+		assert_eq!(Vaults::<Test>::get(kitty_id).unwrap().status, VaultStatus::Active);
+		assert_noop!(KittyFractions::settle(Origin::signed(1), kitty_id), Error::<Test>::NoAuction);
+
+		// 新的拍卖从头开始
+		assert_ok!(KittyFractions::bid(Origin::signed(3), kitty_id, 10));
+		System::assert_last_event(Event::KittyFractions(FractionsEvent::BuyoutBid(
+			3, kitty_id, 10, 21,
+		)));
+	});
+}
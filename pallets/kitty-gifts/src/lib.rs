@@ -0,0 +1,253 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Kitty gifting through claimable links.
+///
+/// An owner escrows a kitty under the hash of an ephemeral sr25519 public key and shares the
+/// secret key off-chain, e.g. as a link. Whoever holds the secret key claims the kitty to any
+/// address with an unsigned extrinsic signed by the ephemeral key, so the recipient does not need
+/// an account or funds. The owner's stake is moved to the recipient as reserved balance. Unclaimed
+/// gifts are returned to the gifter when they expire.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use codec::Encode;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use pallet_kitties::KittyIdOf;
+	use sp_core::sr25519;
+	use sp_runtime::{
+		traits::{Hash, Saturating, Verify},
+		SaturatedConversion,
+	};
+	use sp_std::vec::Vec;
+
+	/// Domain separator of the message signed by the ephemeral gift key.
+	pub const GIFT_CONTEXT: &[u8] = b"kitty-gift";
+
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct Gift<T: Config> {
+		/// Unique id of the gift, part of the signed claim message to prevent replays.
+		pub id: u64,
+		/// The owner who created the gift and gets the kitty back on expiry.
+		pub gifter: T::AccountId,
+		/// Hash of the ephemeral public key allowed to claim the gift.
+		pub key_hash: T::Hash,
+		/// Block from which the gift can no longer be claimed and is returned.
+		pub expires_at: T::BlockNumber,
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_kitties::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Number of blocks a gift can be claimed for.
+		#[pallet::constant]
+		type GiftDuration: Get<Self::BlockNumber>;
+
+		/// The maximum number of gifts that can expire in the same block.
+		#[pallet::constant]
+		type MaxExpiringGifts: Get<u32>;
+
+		/// Priority of unsigned gift claims in the transaction pool.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_gift_id)]
+	/// Id of the next gift.
+	pub type NextGiftId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn gifts)]
+	/// Escrowed kitties waiting to be claimed.
+	pub type Gifts<T: Config> = StorageMap<_, Twox64Concat, KittyIdOf<T>, Gift<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn expiring_gifts)]
+	/// Kitties whose gift expires at the given block. Entries are stale if the gift was claimed.
+	pub type ExpiringGifts<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<KittyIdOf<T>, T::MaxExpiringGifts>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A kitty was escrowed as a gift. \[gifter, kitty_id, gift_id, expires_at\]
+		GiftCreated(T::AccountId, KittyIdOf<T>, u64, T::BlockNumber),
+		/// A gift was claimed. \[gifter, recipient, kitty_id\]
+		GiftClaimed(T::AccountId, T::AccountId, KittyIdOf<T>),
+		/// An unclaimed gift expired and the kitty was returned. \[gifter, kitty_id\]
+		GiftExpired(T::AccountId, KittyIdOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The kitty is not owned by the caller.
+		NotKittyOwner,
+		/// The kitty is already gifted or locked by another pallet.
+		KittyLocked,
+		/// No more gifts can expire in the same block than `MaxExpiringGifts`.
+		TooManyExpiringGifts,
+		/// The kitty has no pending gift.
+		GiftNotFound,
+		/// The gift has expired.
+		GiftExpired,
+		/// The public key does not match the gift's key hash.
+		InvalidGiftKey,
+		/// The claim is not signed by the gift key.
+		InvalidSignature,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 到期未领取的礼物退回给赠送者
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let expiring = <ExpiringGifts<T>>::take(n);
+			let count = expiring.len() as Weight;
+			for kitty_id in expiring {
+				match Self::gifts(kitty_id) {
+					Some(gift) if gift.expires_at == n => {
+						<Gifts<T>>::remove(kitty_id);
+						pallet_kitties::Pallet::<T>::unlock_kitty(kitty_id);
+						Self::deposit_event(Event::GiftExpired(gift.gifter, kitty_id));
+					},
+					_ => (),
+				}
+			}
+			T::DbWeight::get().reads_writes(1 + count, 1 + 2 * count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Escrow a kitty as a gift claimable by the holder of the secret key whose public key
+		/// hashes to `key_hash`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 4))]
+		pub fn create_gift(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			key_hash: T::Hash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			ensure!(kitty.owner == who, Error::<T>::NotKittyOwner);
+			pallet_kitties::Pallet::<T>::lock_kitty(kitty_id)
+				.map_err(|_| Error::<T>::KittyLocked)?;
+
+			let expires_at =
+				<frame_system::Pallet<T>>::block_number().saturating_add(T::GiftDuration::get());
+			<ExpiringGifts<T>>::try_mutate(expires_at, |ids| ids.try_push(kitty_id))
+				.map_err(|_| Error::<T>::TooManyExpiringGifts)?;
+
+			let id = Self::next_gift_id();
+			<NextGiftId<T>>::put(id.wrapping_add(1));
+			<Gifts<T>>::insert(kitty_id, Gift { id, gifter: who.clone(), key_hash, expires_at });
+
+			Self::deposit_event(Event::GiftCreated(who, kitty_id, id, expires_at));
+			Ok(())
+		}
+
+		/// Claim a gift to `recipient`. Submitted unsigned; `signature` is made by the ephemeral
+		/// gift key over `claim_message(kitty_id, gift_id, recipient)`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 6))]
+		pub fn claim_gift(
+			origin: OriginFor<T>,
+			kitty_id: KittyIdOf<T>,
+			recipient: T::AccountId,
+			public: sr25519::Public,
+			signature: sr25519::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let gift = Self::check_claim(kitty_id, &recipient, &public, &signature)?;
+			pallet_kitties::Pallet::<T>::unlock_kitty(kitty_id);
+			pallet_kitties::Pallet::<T>::transfer_with_reserved_stake(kitty_id, &recipient)?;
+			<Gifts<T>>::remove(kitty_id);
+
+			Self::deposit_event(Event::GiftClaimed(gift.gifter, recipient, kitty_id));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (kitty_id, recipient, public, signature) = match call {
+				Call::claim_gift { kitty_id, recipient, public, signature } => {
+					(kitty_id, recipient, public, signature)
+				},
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			let gift =
+				Self::check_claim(*kitty_id, recipient, public, signature).map_err(
+					|e| match e {
+						Error::<T>::GiftNotFound | Error::<T>::GiftExpired => {
+							InvalidTransaction::Stale
+						},
+						_ => InvalidTransaction::BadProof,
+					},
+				)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let longevity = gift.expires_at.saturating_sub(now).saturated_into::<u64>();
+			ValidTransaction::with_tag_prefix("KittyGifts")
+				.priority(T::UnsignedPriority::get())
+				// Only one claim per gift can enter the pool.
+				.and_provides((kitty_id, gift.id))
+				.longevity(longevity)
+				.propagate(true)
+				.build()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The message the ephemeral gift key signs to claim gift `gift_id` to `recipient`.
+		pub fn claim_message(
+			kitty_id: KittyIdOf<T>,
+			gift_id: u64,
+			recipient: &T::AccountId,
+		) -> Vec<u8> {
+			(GIFT_CONTEXT, kitty_id, gift_id, recipient).encode()
+		}
+
+		// Check a claim against the pending gift, shared by dispatch and pool validation.
+		fn check_claim(
+			kitty_id: KittyIdOf<T>,
+			recipient: &T::AccountId,
+			public: &sr25519::Public,
+			signature: &sr25519::Signature,
+		) -> Result<Gift<T>, Error<T>> {
+			let gift = Self::gifts(kitty_id).ok_or(Error::<T>::GiftNotFound)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now < gift.expires_at, Error::<T>::GiftExpired);
+			ensure!(T::Hashing::hash_of(public) == gift.key_hash, Error::<T>::InvalidGiftKey);
+
+			let message = Self::claim_message(kitty_id, gift.id, recipient);
+			ensure!(signature.verify(&message[..], public), Error::<T>::InvalidSignature);
+			Ok(gift)
+		}
+	}
+}
@@ -0,0 +1,124 @@
+use crate::{mock::*, Error, Event as GiftsEvent, Gifts};
+use frame_support::{
+	assert_noop, assert_ok,
+	pallet_prelude::{InvalidTransaction, TransactionSource},
+	unsigned::ValidateUnsigned,
+};
+use sp_core::{sr25519, Pair};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn gift_key() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[7; 32])
+}
+
+// 创建kitty并以礼物形式托管，返回kitty id
+fn create_gift(key: &sr25519::Pair) -> (u32, u32) {
+	assert_ok!(Kitties::create_kitty(Origin::signed(1), 0));
+	let kitty_id = (0, Kitties::collection_supply(0) - 1);
+	let key_hash = BlakeTwo256::hash_of(&key.public());
+	assert_ok!(KittyGifts::create_gift(Origin::signed(1), kitty_id, key_hash));
+	kitty_id
+}
+
+fn claim_call(
+	key: &sr25519::Pair,
+	kitty_id: (u32, u32),
+	signed_for: u64,
+	recipient: u64,
+) -> crate::Call<Test> {
+	let gift_id = KittyGifts::gifts(kitty_id).map(|gift| gift.id).unwrap_or_default();
+	let signature = key.sign(&KittyGifts::claim_message(kitty_id, gift_id, &signed_for));
+	crate::Call::claim_gift { kitty_id, recipient, public: key.public(), signature }
+}
+
+fn validate(call: &crate::Call<Test>) -> Result<(), InvalidTransaction> {
+	KittyGifts::validate_unsigned(TransactionSource::External, call)
+		.map(|_| ())
+		.map_err(|e| match e {
+			frame_support::pallet_prelude::TransactionValidityError::Invalid(e) => e,
+			_ => InvalidTransaction::Call,
+		})
+}
+
+fn claim(
+	key: &sr25519::Pair,
+	kitty_id: (u32, u32),
+	recipient: u64,
+) -> frame_support::dispatch::DispatchResult {
+	match claim_call(key, kitty_id, recipient, recipient) {
+		crate::Call::claim_gift { kitty_id, recipient, public, signature } => {
+			KittyGifts::claim_gift(Origin::none(), kitty_id, recipient, public, signature)
+		},
+		_ => unreachable!(),
+	}
+}
+
+// 礼物托管后kitty被锁定，新账户无需余额即可领取，质押随之转移
+#[test]
+fn claim_gift_to_new_account_works() {
+	new_test_ext().execute_with(|| {
+		let key = gift_key();
+		let kitty_id = create_gift(&key);
+		System::assert_last_event(Event::KittyGifts(GiftsEvent::GiftCreated(1, kitty_id, 0, 11)));
+		assert!(Kitties::is_locked(kitty_id));
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 2, kitty_id),
+			pallet_kitties::Error::<Test>::KittyLocked
+		);
+
+		assert_ok!(validate(&claim_call(&key, kitty_id, 5, 5)));
+		assert_ok!(claim(&key, kitty_id, 5));
+		System::assert_last_event(Event::KittyGifts(GiftsEvent::GiftClaimed(1, 5, kitty_id)));
+
+		assert_eq!(Kitties::kitties(kitty_id).unwrap().owner, 5);
+		assert!(!Kitties::is_locked(kitty_id));
+		assert_eq!(Gifts::<Test>::get(kitty_id), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(5), 100);
+	});
+}
+
+// 签名绑定接收地址，且领取后无法重放
+#[test]
+fn claim_is_bound_to_recipient_and_not_replayable() {
+	new_test_ext().execute_with(|| {
+		let key = gift_key();
+		let kitty_id = create_gift(&key);
+
+		// 签名给5，却提交给6
+		assert_eq!(validate(&claim_call(&key, kitty_id, 5, 6)), Err(InvalidTransaction::BadProof));
+		// 错误的密钥
+		let other = sr25519::Pair::from_seed(&[8; 32]);
+		assert_eq!(
+			validate(&claim_call(&other, kitty_id, 5, 5)),
+			Err(InvalidTransaction::BadProof)
+		);
+		assert_noop!(claim(&other, kitty_id, 5), Error::<Test>::InvalidGiftKey);
+
+		let call = claim_call(&key, kitty_id, 5, 5);
+		assert_ok!(claim(&key, kitty_id, 5));
+		assert_eq!(validate(&call), Err(InvalidTransaction::Stale));
+	});
+}
+
+// 到期未领取，kitty退回赠送者
+#[test]
+fn expired_gift_returns_to_gifter() {
+	new_test_ext().execute_with(|| {
+		let key = gift_key();
+		let kitty_id = create_gift(&key);
+		let call = claim_call(&key, kitty_id, 5, 5);
+
+		run_to_block(10);
+		assert_ok!(validate(&call));
+
+		run_to_block(11);
+		System::assert_last_event(Event::KittyGifts(GiftsEvent::GiftExpired(1, kitty_id)));
+		assert_eq!(validate(&call), Err(InvalidTransaction::Stale));
+		assert_noop!(claim(&key, kitty_id, 5), Error::<Test>::GiftNotFound);
+
+		assert_eq!(Kitties::kitties(kitty_id).unwrap().owner, 1);
+		assert!(!Kitties::is_locked(kitty_id));
+		assert_eq!(Balances::reserved_balance(1), 100);
+	});
+}
@@ -0,0 +1,241 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Kitty staking pool.
+///
+/// Owners stake their kitties into a pool and earn a share of `RewardPerBlock`, paid out of the
+/// pallet's pot account. Each staked kitty contributes a weight derived from the rarity of its
+/// DNA. Rewards are accounted lazily: `on_initialize` only bumps a global reward-per-weight
+/// accumulator, and every staker's share is settled when they stake, unstake or claim.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, ExistenceRequirement},
+		DefaultNoBound, PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_kitties::{BalanceOf, KittyIdOf};
+	use sp_runtime::traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero};
+
+	/// Fixed point precision of the reward-per-weight accumulator.
+	pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+
+	/// A kitty's rarity weight is `2^n` for `n` leading zero bits of its DNA, capped at
+	/// `2^MAX_RARITY_EXPONENT`.
+	pub const MAX_RARITY_EXPONENT: u32 = 4;
+
+	#[derive(
+		Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, DefaultNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct StakerInfo<T: Config> {
+		/// Sum of the rarity weights of all kitties staked by the account.
+		pub weight: u128,
+		/// Rewards already accounted for, i.e. `weight * RewardPerWeight` at the last settlement.
+		pub reward_debt: u128,
+		/// Rewards settled but not yet claimed.
+		pub unclaimed: BalanceOf<T>,
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_kitties::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The staking pool's id, used for deriving the pot account rewards are paid from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Reward distributed among all stakers every block.
+		#[pallet::constant]
+		type RewardPerBlock: Get<BalanceOf<Self>>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn total_weight)]
+	/// Sum of the weights of all staked kitties.
+	pub type TotalWeight<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn reward_per_weight)]
+	/// Accumulated reward per unit of weight, scaled by `ACC_PRECISION`.
+	pub type RewardPerWeight<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn stakers)]
+	/// Reward accounting of every account with at least one staked kitty.
+	pub type Stakers<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, StakerInfo<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn staked_kitties)]
+	/// Staked kitties with their staker and weight.
+	pub type StakedKitties<T: Config> =
+		StorageMap<_, Twox64Concat, KittyIdOf<T>, (T::AccountId, u32), OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A kitty was staked into the pool. \[who, kitty_id, weight\]
+		Staked(T::AccountId, KittyIdOf<T>, u32),
+		/// A kitty was withdrawn from the pool. \[who, kitty_id\]
+		Unstaked(T::AccountId, KittyIdOf<T>),
+		/// Rewards were paid out from the pot. \[who, amount\]
+		RewardClaimed(T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The kitty is not owned by the caller.
+		NotKittyOwner,
+		/// The kitty is already staked or locked by another pallet.
+		KittyLocked,
+		/// The kitty is not staked in the pool.
+		KittyNotStaked,
+		/// The caller has no rewards to claim.
+		NothingToClaim,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			let total_weight = Self::total_weight();
+			if total_weight.is_zero() {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let reward = T::RewardPerBlock::get().saturated_into::<u128>();
+			let increment = reward.saturating_mul(ACC_PRECISION) / total_weight;
+			<RewardPerWeight<T>>::mutate(|acc| *acc = acc.saturating_add(increment));
+			T::DbWeight::get().reads_writes(2, 1)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Stake a kitty into the pool. The kitty is locked until it is unstaked.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 4))]
+		pub fn stake(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let kitty = pallet_kitties::Pallet::<T>::kitties(kitty_id)
+				.ok_or(pallet_kitties::Error::<T>::KittyNotExist)?;
+			ensure!(kitty.owner == who, Error::<T>::NotKittyOwner);
+			pallet_kitties::Pallet::<T>::lock_kitty(kitty_id)
+				.map_err(|_| Error::<T>::KittyLocked)?;
+
+			let weight = Self::rarity_weight(&kitty.dna);
+			Self::update_stake(&who, |staked| staked.saturating_add(weight.into()));
+			<TotalWeight<T>>::mutate(|total| *total = total.saturating_add(weight.into()));
+			<StakedKitties<T>>::insert(kitty_id, (who.clone(), weight));
+
+			Self::deposit_event(Event::Staked(who, kitty_id, weight));
+			Ok(())
+		}
+
+		/// Withdraw a kitty from the pool and unlock it. Pending rewards are kept and can be
+		/// claimed later.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 4))]
+		pub fn unstake(origin: OriginFor<T>, kitty_id: KittyIdOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (staker, weight) =
+				Self::staked_kitties(kitty_id).ok_or(Error::<T>::KittyNotStaked)?;
+			ensure!(staker == who, Error::<T>::NotKittyOwner);
+
+			Self::update_stake(&who, |staked| staked.saturating_sub(weight.into()));
+			<TotalWeight<T>>::mutate(|total| *total = total.saturating_sub(weight.into()));
+			<StakedKitties<T>>::remove(kitty_id);
+			pallet_kitties::Pallet::<T>::unlock_kitty(kitty_id);
+
+			Self::deposit_event(Event::Unstaked(who, kitty_id));
+			Ok(())
+		}
+
+		/// Pay out all rewards earned so far from the pot account.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn claim(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut staker = Self::stakers(&who).ok_or(Error::<T>::NothingToClaim)?;
+			let amount = staker.unclaimed.saturating_add(Self::accrued(&staker));
+			ensure!(!amount.is_zero(), Error::<T>::NothingToClaim);
+
+			<T as pallet_kitties::Config>::Currency::transfer(
+				&Self::account_id(),
+				&who,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			staker.unclaimed = Zero::zero();
+			staker.reward_debt = Self::reward_for(staker.weight);
+			if staker.weight.is_zero() {
+				<Stakers<T>>::remove(&who);
+			} else {
+				<Stakers<T>>::insert(&who, staker);
+			}
+
+			Self::deposit_event(Event::RewardClaimed(who, amount));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding the reward pot.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Weight of a kitty in the pool: rarer DNA (more leading zero bits) earns more, doubling
+		/// per zero bit up to `2^MAX_RARITY_EXPONENT`.
+		pub fn rarity_weight(dna: &[u8; 16]) -> u32 {
+			let zeros = u128::from_be_bytes(*dna).leading_zeros();
+			1u32 << zeros.min(MAX_RARITY_EXPONENT)
+		}
+
+		/// Rewards `who` could claim right now.
+		pub fn pending_rewards(who: &T::AccountId) -> BalanceOf<T> {
+			match Self::stakers(who) {
+				Some(staker) => staker.unclaimed.saturating_add(Self::accrued(&staker)),
+				None => Zero::zero(),
+			}
+		}
+
+		// Rewards accrued since the staker's last settlement.
+		fn accrued(staker: &StakerInfo<T>) -> BalanceOf<T> {
+			let earned = Self::reward_for(staker.weight).saturating_sub(staker.reward_debt);
+			earned.saturated_into()
+		}
+
+		fn reward_for(weight: u128) -> u128 {
+			weight.saturating_mul(Self::reward_per_weight()) / ACC_PRECISION
+		}
+
+		// Settle the pending rewards of `who` and apply a new staked weight.
+		fn update_stake(who: &T::AccountId, f: impl FnOnce(u128) -> u128) {
+			let mut staker = Self::stakers(who).unwrap_or_default();
+			staker.unclaimed = staker.unclaimed.saturating_add(Self::accrued(&staker));
+			staker.weight = f(staker.weight);
+			staker.reward_debt = Self::reward_for(staker.weight);
+
+			if staker.weight.is_zero() && staker.unclaimed.is_zero() {
+				<Stakers<T>>::remove(who);
+			} else {
+				<Stakers<T>>::insert(who, staker);
+			}
+		}
+	}
+}
@@ -0,0 +1,106 @@
+use crate::{mock::*, Error, Event as StakingEvent, StakedKitties, Stakers};
+use frame_support::{assert_noop, assert_ok};
+use pallet_kitties::Gender;
+
+const RARE_DNA: [u8; 16] = [0; 16];
+const COMMON_DNA: [u8; 16] = [0xff; 16];
+
+fn mint(owner: u64, dna: [u8; 16]) -> (u32, u32) {
+	Kitties::mint(&owner, 0, Some(dna), Some(Gender::Male)).unwrap()
+}
+
+#[test]
+fn rarity_weight_works() {
+	assert_eq!(KittyStaking::rarity_weight(&COMMON_DNA), 1);
+	assert_eq!(KittyStaking::rarity_weight(&[0x40; 16]), 2);
+	assert_eq!(KittyStaking::rarity_weight(&[0x10; 16]), 8);
+	assert_eq!(KittyStaking::rarity_weight(&RARE_DNA), 16);
+}
+
+// 质押后kitty被锁定，解除质押后可以转移
+#[test]
+fn stake_locks_kitty_until_unstaked() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = mint(1, RARE_DNA);
+		assert_ok!(KittyStaking::stake(Origin::signed(1), kitty_id));
+		System::assert_last_event(Event::KittyStaking(StakingEvent::Staked(1, kitty_id, 16)));
+
+		assert_eq!(StakedKitties::<Test>::get(kitty_id), Some((1, 16)));
+		assert_eq!(KittyStaking::total_weight(), 16);
+		assert!(Kitties::is_locked(kitty_id));
+		assert_noop!(
+			Kitties::transfer(Origin::signed(1), 2, kitty_id),
+			pallet_kitties::Error::<Test>::KittyLocked
+		);
+		assert_noop!(KittyStaking::stake(Origin::signed(1), kitty_id), Error::<Test>::KittyLocked);
+
+		assert_ok!(KittyStaking::unstake(Origin::signed(1), kitty_id));
+		System::assert_last_event(Event::KittyStaking(StakingEvent::Unstaked(1, kitty_id)));
+		assert_eq!(KittyStaking::total_weight(), 0);
+		assert!(!Kitties::is_locked(kitty_id));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, kitty_id));
+	});
+}
+
+#[test]
+fn stake_and_unstake_fail() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = mint(1, RARE_DNA);
+		assert_noop!(
+			KittyStaking::stake(Origin::signed(2), kitty_id),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittyStaking::unstake(Origin::signed(1), kitty_id),
+			Error::<Test>::KittyNotStaked
+		);
+
+		assert_ok!(KittyStaking::stake(Origin::signed(1), kitty_id));
+		assert_noop!(
+			KittyStaking::unstake(Origin::signed(2), kitty_id),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(KittyStaking::claim(Origin::signed(2)), Error::<Test>::NothingToClaim);
+	});
+}
+
+// 奖励按稀有度加权分配
+#[test]
+fn rewards_are_weighted_by_rarity() {
+	new_test_ext().execute_with(|| {
+		let rare = mint(1, RARE_DNA);
+		let common = mint(2, COMMON_DNA);
+		assert_ok!(KittyStaking::stake(Origin::signed(1), rare));
+		assert_ok!(KittyStaking::stake(Origin::signed(2), common));
+
+		run_to_block(2);
+		// 100 per block split 16:1
+		assert_eq!(KittyStaking::pending_rewards(&1), 94);
+		assert_eq!(KittyStaking::pending_rewards(&2), 5);
+
+		assert_ok!(KittyStaking::claim(Origin::signed(1)));
+		System::assert_last_event(Event::KittyStaking(StakingEvent::RewardClaimed(1, 94)));
+		assert_eq!(Balances::free_balance(1), 10_000 + 94);
+		assert_eq!(Balances::free_balance(KittyStaking::account_id()), POT_BALANCE - 94);
+		assert_eq!(KittyStaking::pending_rewards(&1), 0);
+		assert_noop!(KittyStaking::claim(Origin::signed(1)), Error::<Test>::NothingToClaim);
+	});
+}
+
+#[test]
+fn unstaking_keeps_earned_rewards() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = mint(1, COMMON_DNA);
+		assert_ok!(KittyStaking::stake(Origin::signed(1), kitty_id));
+		run_to_block(4);
+		assert_ok!(KittyStaking::unstake(Origin::signed(1), kitty_id));
+		assert_eq!(KittyStaking::pending_rewards(&1), 300);
+
+		// nothing accrues while no kitty is staked
+		run_to_block(6);
+		assert_eq!(KittyStaking::pending_rewards(&1), 300);
+
+		assert_ok!(KittyStaking::claim(Origin::signed(1)));
+		assert_eq!(Stakers::<Test>::get(1), None);
+	});
+}
@@ -0,0 +1,47 @@
+//! Runtime API definition for the proof-of-existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_poe::{Attestation, Batch, OwnershipRecord, Proof};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Read access to claims, so the node can prove who held a claim when.
+	pub trait PoeApi<AccountId, Hash, Balance, BlockNumber, Moment> where
+		AccountId: Codec,
+		Hash: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+	{
+		/// The claim on a document digest, if it exists, with the blocks and times it was
+		/// created and last transferred in.
+		fn claim(claim: Hash) -> Option<Proof<AccountId, Balance, BlockNumber, Moment>>;
+
+		/// The previous owners of a claim, oldest first, at most `MaxClaimHistory` of them.
+		fn claim_history(claim: Hash) -> Vec<OwnershipRecord<AccountId, BlockNumber, Moment>>;
+
+		/// All attestations of a claim, with whether the attester is currently trusted.
+		fn attestations(
+			claim: Hash,
+		) -> Vec<(AccountId, Attestation<BlockNumber, Hash, Balance>, bool)>;
+
+		/// The signers that co-signed a joint claim, empty for other claims.
+		fn joint_signers(claim: Hash) -> Vec<AccountId>;
+
+		/// The document digest a commitment claim was revealed to commit to, if it was.
+		fn revealed_document(claim: Hash) -> Option<Hash>;
+
+		/// Whether `claim` is the salted commitment to `document_hash`, without revealing it
+		/// on-chain.
+		fn verify_commitment(claim: Hash, salt: [u8; 32], document_hash: Hash) -> bool;
+
+		/// The batch anchored under a Merkle root, if it exists.
+		fn batch(root: Hash) -> Option<Batch<AccountId, Balance, BlockNumber>>;
+
+		/// Whether `proof`, the siblings from the leaf up, shows that the claim digest `leaf` is
+		/// in the anchored batch `root`.
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool;
+	}
+}
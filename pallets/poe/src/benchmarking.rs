@@ -1,20 +1,207 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-poe
 
 use super::*;
 
 #[allow(unused)]
-use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use crate::Pallet as Poe;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, ReservableCurrency};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, One, Zero};
+use sp_std::{vec, vec::Vec};
+
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	caller
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, 0);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
-		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+	create_claim {
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		let expires_at = T::MaxClaimDuration::get();
+	}: _(RawOrigin::Signed(caller.clone()), claim, Some(expires_at))
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|proof| proof.owner), Some(caller));
+	}
+
+	revoke_claim {
+		let a in 0 .. T::MaxAttestations::get() + T::MaxTrustedAttestations::get();
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		let expires_at = Some(T::MaxClaimDuration::get());
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, expires_at)?;
+		for i in 0 .. a {
+			let attester = funded_account::<T>("attester", i);
+			// Attesters past `MaxAttestations` must be trusted.
+			if i >= T::MaxAttestations::get() {
+				Poe::<T>::add_trusted_attester(RawOrigin::Root.into(), attester.clone())?;
+			}
+			Poe::<T>::attest(RawOrigin::Signed(attester).into(), claim, claim)?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), claim)
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+		assert!(T::Currency::reserved_balance(&caller).is_zero());
+		assert_eq!(AttestationCount::<T>::get(&claim), 0);
+		assert_eq!(TrustedAttestationCount::<T>::get(&claim), 0);
+	}
+
+	renew_claim {
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		let expires_at = T::MaxClaimDuration::get();
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, Some(expires_at))?;
+		let renewed = expires_at - One::one();
+	}: _(RawOrigin::Signed(caller), claim, renewed)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).and_then(|proof| proof.expires_at), Some(renewed));
+	}
+
+	reveal {
+		let caller = funded_caller::<T>();
+		let salt = [7u8; 32];
+		let document_hash = Poe::<T>::claim_hash(b"document");
+		let claim = Poe::<T>::commitment(&salt, &document_hash);
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, None)?;
+	}: _(RawOrigin::Signed(caller), claim, salt, document_hash)
+	verify {
+		assert_eq!(Revelations::<T>::get(&claim), Some(document_hash));
+	}
+
+	transfer_claim {
+		let caller = funded_caller::<T>();
+		let dest: T::AccountId = account("dest", 0, 0);
+		let claim = Poe::<T>::claim_hash(b"document");
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, None)?;
+	}: _(RawOrigin::Signed(caller), claim, dest.clone())
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|proof| proof.owner), Some(dest));
+	}
+
+	set_claim_metadata {
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, None)?;
+		let content_type = vec![b'a'; T::MaxContentTypeLength::get() as usize];
+		let description = vec![b'd'; T::MaxDescriptionLength::get() as usize];
+		let uri = vec![b'u'; T::MaxUriLength::get() as usize];
+	}: _(RawOrigin::Signed(caller), claim, content_type, description, uri, 1)
+	verify {
+		assert_eq!(Metadata::<T>::get(&claim).map(|metadata| metadata.version), Some(1));
+	}
+
+	clear_claim_metadata {
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		Poe::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim, None)?;
+		let uri = vec![b'u'; T::MaxUriLength::get() as usize];
+		Poe::<T>::set_claim_metadata(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim,
+			Vec::new(),
+			Vec::new(),
+			uri,
+			1,
+		)?;
+	}: _(RawOrigin::Signed(caller), claim)
+	verify {
+		assert!(!Metadata::<T>::contains_key(&claim));
+	}
+
+	propose_joint_claim {
+		let s in 1 .. T::MaxJointSigners::get();
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"contract");
+		let signers: Vec<T::AccountId> = (0 .. s).map(|i| account("signer", i, 0)).collect();
+		let deadline = T::MaxJointProposalDuration::get();
+	}: _(RawOrigin::Signed(caller), claim, signers, s, deadline)
+	verify {
+		assert!(JointProposals::<T>::contains_key(&claim));
+	}
+
+	// The last co-signature, which finalizes the claim.
+	cosign {
+		let s in 1 .. T::MaxJointSigners::get();
+		let caller = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"contract");
+		let signers: Vec<T::AccountId> = (0 .. s).map(|i| account("signer", i, 0)).collect();
+		let deadline = T::MaxJointProposalDuration::get();
+		Poe::<T>::propose_joint_claim(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim,
+			signers.clone(),
+			s,
+			deadline,
+		)?;
+		for signer in &signers[1 ..] {
+			Poe::<T>::cosign(RawOrigin::Signed(signer.clone()).into(), claim)?;
+		}
+	}: _(RawOrigin::Signed(signers[0].clone()), claim)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|proof| proof.owner), Some(caller));
+	}
+
+	attest {
+		let owner = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		Poe::<T>::create_claim(RawOrigin::Signed(owner).into(), claim, None)?;
+		let attester = funded_account::<T>("attester", 0);
+	}: _(RawOrigin::Signed(attester.clone()), claim, claim)
+	verify {
+		assert!(Attestations::<T>::contains_key(&claim, &attester));
+	}
+
+	withdraw_attestation {
+		let owner = funded_caller::<T>();
+		let claim = Poe::<T>::claim_hash(b"document");
+		Poe::<T>::create_claim(RawOrigin::Signed(owner).into(), claim, None)?;
+		let attester = funded_account::<T>("attester", 0);
+		Poe::<T>::attest(RawOrigin::Signed(attester.clone()).into(), claim, claim)?;
+	}: _(RawOrigin::Signed(attester.clone()), claim)
+	verify {
+		assert!(!Attestations::<T>::contains_key(&claim, &attester));
+	}
+
+	add_trusted_attester {
+		let attester: T::AccountId = account("attester", 0, 0);
+	}: _(RawOrigin::Root, attester.clone())
+	verify {
+		assert!(TrustedAttesters::<T>::contains_key(&attester));
+	}
+
+	remove_trusted_attester {
+		let attester: T::AccountId = account("attester", 0, 0);
+		Poe::<T>::add_trusted_attester(RawOrigin::Root.into(), attester.clone())?;
+	}: _(RawOrigin::Root, attester.clone())
+	verify {
+		assert!(!TrustedAttesters::<T>::contains_key(&attester));
+	}
+
+	anchor_batch {
+		let caller = funded_caller::<T>();
+		let root = Poe::<T>::claim_hash(b"root");
+	}: _(RawOrigin::Signed(caller.clone()), root, 1_000)
+	verify {
+		assert_eq!(Batches::<T>::get(&root).map(|batch| batch.owner), Some(caller));
+	}
+
+	revoke_batch {
+		let caller = funded_caller::<T>();
+		let root = Poe::<T>::claim_hash(b"root");
+		Poe::<T>::anchor_batch(RawOrigin::Signed(caller.clone()).into(), root, 1_000)?;
+	}: _(RawOrigin::Signed(caller.clone()), root)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert!(!Batches::<T>::contains_key(&root));
+		assert!(T::Currency::reserved_balance(&caller).is_zero());
 	}
 
-	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+	impl_benchmark_test_suite!(Poe, crate::mock::new_test_ext(), crate::mock::Test);
 }
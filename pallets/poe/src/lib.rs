@@ -1,123 +1,1060 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod merkle;
+pub mod migrations;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests; // filename
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[frame_support::pallet] // 宏
 pub mod pallet {
-
-use frame_support::pallet_prelude::*;
+	use super::{merkle, WeightInfo};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{BalanceStatus, Currency, ReservableCurrency, Time},
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_std::prelude::*;
+	use sp_runtime::traits::{Hash, Saturating};
+	use sp_std::vec::Vec;
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+	pub type ProofOf<T> = Proof<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+		MomentOf<T>,
+	>;
+	pub type OwnershipRecordOf<T> = OwnershipRecord<
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::BlockNumber,
+		MomentOf<T>,
+	>;
+	pub type BatchOf<T> = Batch<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+	pub type AttestationOf<T> = Attestation<
+		<T as frame_system::Config>::BlockNumber,
+		<T as frame_system::Config>::Hash,
+		BalanceOf<T>,
+	>;
+
+	/// The storage version: 1 keyed claims by the digest of the document instead of its bytes and
+	/// stored them as a `Proof`.
+	pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// A claim on a document.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Proof<AccountId, Balance, BlockNumber, Moment> {
+		pub owner: AccountId,
+		/// Block the claim was created in.
+		pub created_at: BlockNumber,
+		/// Time of the block the claim was created in, as given by `Config::Time`.
+		pub created_time: Moment,
+		/// Block the claim was transferred to `owner` in, `created_at` if it never was.
+		pub owned_since: BlockNumber,
+		/// Time of the block `owned_since`.
+		pub owned_since_time: Moment,
+		/// Amount reserved from the owner.
+		pub deposit: Balance,
+		/// Block the claim is removed in and its deposit released, if any.
+		pub expires_at: Option<BlockNumber>,
+	}
+
+	/// A previous owner of a claim.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct OwnershipRecord<AccountId, BlockNumber, Moment> {
+		pub owner: AccountId,
+		/// Block `owner` created the claim or received it in.
+		pub since: BlockNumber,
+		/// Time of the block `since`.
+		pub since_time: Moment,
+		/// Block `owner` transferred the claim in.
+		pub until: BlockNumber,
+		/// Time of the block `until`.
+		pub until_time: Moment,
+	}
+
+	/// What a claimed document is, set by the owner of the claim.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct ClaimMetadata<T: Config> {
+		/// MIME type of the document, e.g. `application/pdf`.
+		pub content_type: BoundedVec<u8, T::MaxContentTypeLength>,
+		/// Short description of the document.
+		pub description: BoundedVec<u8, T::MaxDescriptionLength>,
+		/// Where the document can be found, e.g. an IPFS or HTTPS URI.
+		pub uri: BoundedVec<u8, T::MaxUriLength>,
+		/// Version of the document, chosen by the owner.
+		pub version: u32,
+		/// Amount reserved from the owner, `ClaimDepositPerByte` per byte of the fields above.
+		pub deposit: BalanceOf<T>,
+	}
+
+	/// A claim waiting for the co-signatures of several parties, e.g. to a contract.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct JointProposal<T: Config> {
+		/// Account that proposed the claim and owns it once finalized.
+		pub proposer: T::AccountId,
+		/// Accounts asked to co-sign.
+		pub signers: BoundedVec<T::AccountId, T::MaxJointSigners>,
+		/// Signers that co-signed so far.
+		pub approvals: BoundedVec<T::AccountId, T::MaxJointSigners>,
+		/// Number of co-signatures that finalizes the claim.
+		pub threshold: u32,
+		/// Block the proposal lapses in if not finalized before.
+		pub deadline: T::BlockNumber,
+		/// Amount reserved from the proposer.
+		pub deposit: BalanceOf<T>,
+	}
+
+	/// The Merkle root of many claims, anchored at once with `anchor_batch`. See `merkle`.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Batch<AccountId, Balance, BlockNumber> {
+		pub owner: AccountId,
+		/// Number of leaves of the tree, as declared by the owner.
+		pub leaf_count: u32,
+		/// Block the batch was anchored in.
+		pub anchored_at: BlockNumber,
+		/// Amount reserved from the owner.
+		pub deposit: Balance,
+	}
+
+	/// A countersignature of a claim by a third party, e.g. a notary or an auditor.
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Attestation<BlockNumber, Hash, Balance> {
+		/// Block the claim was attested in.
+		pub block_number: BlockNumber,
+		/// Digest of the attester's note, e.g. an audit report, kept off-chain.
+		pub note: Hash,
+		/// Amount reserved from the attester.
+		pub deposit: Balance,
+		/// Whether the attester was trusted when attesting, which decides whether the
+		/// attestation counts toward `MaxTrustedAttestations` or `MaxAttestations`.
+		pub trusted: bool,
+	}
 
 	// 定义模块需要配置
 	#[pallet::config]
-	pub trait Config:frame_system::Config {
-		#[pallet::constant]
-		type MaxClaimLength: Get<u32>;
+	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+		/// The currency the claim deposits are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The clock claims and transfers are timestamped with, e.g. `pallet_timestamp`.
+		type Time: Time;
+
+		/// Amount reserved for every claim.
+		#[pallet::constant]
+		type ClaimDepositBase: Get<BalanceOf<Self>>;
+
+		/// Amount reserved per byte a claim takes in storage, on top of `ClaimDepositBase`.
+		#[pallet::constant]
+		type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The latest a claim can expire, in blocks after it is created or renewed.
+		#[pallet::constant]
+		type MaxClaimDuration: Get<Self::BlockNumber>;
+
+		/// The maximum number of claims and joint claim proposals that can expire in the same
+		/// block. Bounds the work of `on_initialize`.
+		#[pallet::constant]
+		type MaxExpiringClaims: Get<u32>;
+
+		/// The maximum number of signers of a joint claim.
+		#[pallet::constant]
+		type MaxJointSigners: Get<u32>;
+
+		/// The latest a joint claim proposal can lapse, in blocks after it is proposed.
+		#[pallet::constant]
+		type MaxJointProposalDuration: Get<Self::BlockNumber>;
+
+		/// The maximum length of the content type of a claim.
+		#[pallet::constant]
+		type MaxContentTypeLength: Get<u32>;
+
+		/// The maximum length of the description of a claim.
+		#[pallet::constant]
+		type MaxDescriptionLength: Get<u32>;
+
+		/// The maximum length of the URI of a claim.
+		#[pallet::constant]
+		type MaxUriLength: Get<u32>;
+
+		/// The maximum number of previous owners kept per claim. The oldest ones are dropped
+		/// first.
+		#[pallet::constant]
+		type MaxClaimHistory: Get<u32>;
+
+		/// Amount reserved from an attester for every attestation.
+		#[pallet::constant]
+		type AttestationDeposit: Get<BalanceOf<Self>>;
+
+		/// The maximum number of attestations of a claim by untrusted attesters.
+		#[pallet::constant]
+		type MaxAttestations: Get<u32>;
+
+		/// The maximum number of attestations of a claim by trusted attesters, kept apart so
+		/// that untrusted attesters cannot crowd them out.
+		#[pallet::constant]
+		type MaxTrustedAttestations: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 	// 定义模块
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// 存证：文档摘要（见 `claim_hash`）=> 所有者、区块和押金
+	#[pallet::storage]
+	#[pallet::getter(fn proofs)]
+	pub type Proofs<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, ProofOf<T>>;
+
+	/// 在指定区块过期的存证和联合存证提案。存证被撤销或续期、提案完成后不再在原区块过期
 	#[pallet::storage]
-	pub type Proofs<T: Config> = StorageMap<
+	#[pallet::getter(fn expiring_claims)]
+	pub type ExpiringClaims<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::Hash, T::MaxExpiringClaims>,
+		ValueQuery,
+	>;
+
+	/// 等待共同签名的联合存证提案
+	#[pallet::storage]
+	#[pallet::getter(fn joint_proposals)]
+	pub type JointProposals<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, JointProposal<T>>;
+
+	/// 联合存证的共同签名人
+	#[pallet::storage]
+	#[pallet::getter(fn joint_signers)]
+	pub type JointSigners<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BoundedVec<T::AccountId, T::MaxJointSigners>>;
+
+	/// 已揭示的承诺存证：承诺 => 文档摘要
+	#[pallet::storage]
+	#[pallet::getter(fn revelations)]
+	pub type Revelations<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, T::Hash>;
+
+	/// 存证的元数据，由所有者设置
+	#[pallet::storage]
+	#[pallet::getter(fn metadata)]
+	pub type Metadata<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, ClaimMetadata<T>>;
+
+	/// 存证的历史所有者，从早到晚排列，不包括当前所有者
+	#[pallet::storage]
+	#[pallet::getter(fn claim_history)]
+	pub type ClaimHistory<T: Config> = StorageMap<
 		_,
 		Blake2_128Concat,
-		BoundedVec<u8, T::MaxClaimLength>,
-		(T::AccountId, T::BlockNumber),
+		T::Hash,
+		BoundedVec<OwnershipRecordOf<T>, T::MaxClaimHistory>,
+		ValueQuery,
 	>;
 
+	/// 存证的第三方背书：存证 => 背书人 => 背书
+	#[pallet::storage]
+	#[pallet::getter(fn attestations)]
+	pub type Attestations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		AttestationOf<T>,
+	>;
+
+	/// 每个存证的非可信背书数量，不超过 `MaxAttestations`
+	#[pallet::storage]
+	#[pallet::getter(fn attestation_count)]
+	pub type AttestationCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, u32, ValueQuery>;
+
+	/// 每个存证的可信背书数量，不超过 `MaxTrustedAttestations`
+	#[pallet::storage]
+	#[pallet::getter(fn trusted_attestation_count)]
+	pub type TrustedAttestationCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, u32, ValueQuery>;
+
+	/// 批量存证：Merkle根 => 所有者、叶子数量、区块和押金
+	#[pallet::storage]
+	#[pallet::getter(fn batches)]
+	pub type Batches<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, BatchOf<T>>;
+
+	/// 由root管理的可信背书人
+	#[pallet::storage]
+	#[pallet::getter(fn trusted_attesters)]
+	pub type TrustedAttesters<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		ClaimCreated(T::AccountId, Vec<u8>),
-		ClaimRevoked(T::AccountId, Vec<u8>),
+		/// A claim was created. \[owner, claim, time\]
+		ClaimCreated(T::AccountId, T::Hash, MomentOf<T>),
+		/// A claim was revoked by its owner. \[owner, claim\]
+		ClaimRevoked(T::AccountId, T::Hash),
+		/// A claim expired and its deposit was released. \[owner, claim\]
+		ClaimExpired(T::AccountId, T::Hash),
+		/// The owner of a claim set a new expiry. \[owner, claim, expires_at\]
+		ClaimRenewed(T::AccountId, T::Hash, T::BlockNumber),
+		/// A claim was transferred to a new owner. \[from, to, claim, time\]
+		ClaimTransferred(T::AccountId, T::AccountId, T::Hash, MomentOf<T>),
+		/// The owner of a claim set its metadata. \[owner, claim\]
+		ClaimMetadataSet(T::AccountId, T::Hash),
+		/// The owner of a claim cleared its metadata. \[owner, claim\]
+		ClaimMetadataCleared(T::AccountId, T::Hash),
+		/// A claim was attested. \[attester, claim, note\]
+		ClaimAttested(T::AccountId, T::Hash, T::Hash),
+		/// An attester withdrew their attestation of a claim. \[attester, claim\]
+		AttestationWithdrawn(T::AccountId, T::Hash),
+		/// Root added a trusted attester. \[attester\]
+		TrustedAttesterAdded(T::AccountId),
+		/// Root removed a trusted attester. \[attester\]
+		TrustedAttesterRemoved(T::AccountId),
+		/// The Merkle root of a batch of claims was anchored. \[owner, root, leaf_count\]
+		BatchAnchored(T::AccountId, T::Hash, u32),
+		/// A batch was revoked by its owner. \[owner, root\]
+		BatchRevoked(T::AccountId, T::Hash),
+		/// The owner of a claim revealed the document digest it commits to.
+		/// \[owner, claim, document_hash\]
+		ClaimRevealed(T::AccountId, T::Hash, T::Hash),
+		/// A joint claim was proposed. \[proposer, claim, threshold, deadline\]
+		JointClaimProposed(T::AccountId, T::Hash, u32, T::BlockNumber),
+		/// A signer co-signed a joint claim proposal. \[signer, claim\]
+		JointClaimCosigned(T::AccountId, T::Hash),
+		/// A joint claim reached its threshold and was created. \[owner, claim, time\]
+		JointClaimFinalized(T::AccountId, T::Hash, MomentOf<T>),
+		/// A joint claim proposal reached its deadline without enough co-signatures.
+		/// \[proposer, claim\]
+		JointClaimLapsed(T::AccountId, T::Hash),
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		ProofAlreadyExist,
-		ClaimTooLong,
 		ClaimNotExist,
 		NostClaimOwner,
+		/// The expiry is not after the current block or more than `MaxClaimDuration` after it.
+		InvalidExpiry,
+		/// `MaxExpiringClaims` claims or proposals expire in the requested block already.
+		TooManyExpiringClaims,
+		/// The content type is longer than `MaxContentTypeLength`.
+		ContentTypeTooLong,
+		/// The description is longer than `MaxDescriptionLength`.
+		DescriptionTooLong,
+		/// The URI is longer than `MaxUriLength`.
+		UriTooLong,
+		/// The claim has no metadata.
+		MetadataNotExist,
+		/// The account already attested the claim.
+		AlreadyAttested,
+		/// The claim has `MaxAttestations` attestations already.
+		TooManyAttestations,
+		/// The account did not attest the claim.
+		AttestationNotExist,
+		/// The account is already a trusted attester.
+		AlreadyTrusted,
+		/// The account is not a trusted attester.
+		NotTrusted,
+		/// A batch with the same Merkle root is anchored already.
+		BatchAlreadyExist,
+		/// No batch with the Merkle root is anchored.
+		BatchNotExist,
+		/// A batch must have at least one leaf.
+		EmptyBatch,
+		/// The claim is not the commitment of the salt and document digest.
+		InvalidReveal,
+		/// The claim was revealed already.
+		AlreadyRevealed,
+		/// More signers than `MaxJointSigners`.
+		TooManyJointSigners,
+		/// An account is listed twice as signer.
+		DuplicateJointSigner,
+		/// The threshold is zero or more than the number of signers.
+		InvalidThreshold,
+		/// The deadline is not after the current block or more than `MaxJointProposalDuration`
+		/// after it.
+		InvalidDeadline,
+		/// No joint claim is proposed for the digest.
+		ProposalNotExist,
+		/// The account is not a signer of the proposal.
+		NotJointSigner,
+		/// The signer co-signed the proposal already.
+		AlreadyCosigned,
 	}
 
 	#[pallet::hooks]
-	impl<T:Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 删除在本区块过期的存证并退还押金
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			for claim in ExpiringClaims::<T>::take(n) {
+				weight = weight
+					.saturating_add(Self::expire_claim(claim, n))
+					.saturating_add(Self::lapse_joint_proposal(claim, n));
+			}
+			weight
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(0)]
-		pub fn create_claim(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+		/// Claim a document by its digest, reserving `claim_deposit()` from the caller. A claim
+		/// with `expires_at` is removed in that block and its deposit released, unless renewed.
+		///
+		/// To claim a document without disclosing its digest, claim the salted commitment
+		/// `commitment(salt, claim_hash(document))` instead and `reveal` it later.
+		#[pallet::weight(T::WeightInfo::create_claim())]
+		pub fn create_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?; // 校验发送方
-			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
-				.map_err(|_| Error::<T>::ClaimTooLong)?; // 校验长度
 
-			// 确认当前proof还没被存储
-			ensure!(!Proofs::<T>::contains_key(&bounded_claim), Error::<T>::ProofAlreadyExist);
+			// 确认当前proof还没被存储，也没有等待签名的联合存证
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			ensure!(!JointProposals::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			if let Some(expires_at) = expires_at {
+				Self::schedule_expiry(claim, expires_at)?;
+			}
+			// 质押押金，防止免费占用存储
+			let deposit = Self::claim_deposit();
+			T::Currency::reserve(&sender, deposit)?;
 			// 插入键值对
+			let now = frame_system::Pallet::<T>::block_number();
+			let time = T::Time::now();
 			Proofs::<T>::insert(
-				&bounded_claim,
-				(sender.clone(), frame_system::Pallet::<T>::block_number()),
+				&claim,
+				Proof {
+					owner: sender.clone(),
+					created_at: now,
+					created_time: time,
+					owned_since: now,
+					owned_since_time: time,
+					deposit,
+					expires_at,
+				},
 			);
 			// 触发事件 并返回ok
-			Self::deposit_event(Event::ClaimCreated(sender, claim));
+			Self::deposit_event(Event::ClaimCreated(sender, claim, time));
 			Ok(().into())
 		}
-		#[pallet::weight(0)]
-		pub fn revoke_claim(
-			origin: OriginFor<T>,
-			claim: Vec<u8>,
-		) -> DispatchResultWithPostInfo {
+
+		/// Revoke a claim of the caller, releasing its deposit. Its attestations are removed and
+		/// their deposits released as well.
+		#[pallet::weight(T::WeightInfo::revoke_claim(
+			T::MaxAttestations::get().saturating_add(T::MaxTrustedAttestations::get())
+		))]
+		pub fn revoke_claim(origin: OriginFor<T>, claim: T::Hash) -> DispatchResultWithPostInfo {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://substrate.dev/docs/en/knowledgebase/runtime/origin
 			let sender = ensure_signed(origin)?;
 
-			let bounded_claim =
-			BoundedVec::<u8, <T as Config>::MaxClaimLength>::try_from(claim.clone()).map_err(|_| Error::<T>::ClaimTooLong)?;
-
-
-			// Verify that the specified proof has been claimed.
-			// ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-
 			// Get owner of the claim.
-			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
 			// Verify that sender of the current call is the claim owner.
-			ensure!(sender == owner, Error::<T>::NostClaimOwner);
+			ensure!(sender == proof.owner, Error::<T>::NostClaimOwner);
 
 			// Remove claim from storage.
-			Proofs::<T>::remove(&bounded_claim);
+			if let Some(expires_at) = proof.expires_at {
+				Self::unschedule_expiry(claim, expires_at);
+			}
+			let attestations = Self::remove_claim(claim, &proof);
 
 			// Emit an event that the claim was erased.
 			Self::deposit_event(Event::ClaimRevoked(sender, claim));
 
-			Ok(().into())
+			Ok(Some(T::WeightInfo::revoke_claim(attestations)).into())
 		}
 
-		#[pallet::weight(0)]
-		pub fn transfer_claim(origin: OriginFor<T>, claim: Vec<u8>, dest: T::AccountId) -> DispatchResultWithPostInfo {
+		/// Transfer a claim of the caller to `dest`, together with its deposit, which the caller
+		/// first tops up to `claim_deposit()` if it is short. The caller is added to the claim's
+		/// history.
+		#[pallet::weight(T::WeightInfo::transfer_claim())]
+		pub fn transfer_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			dest: T::AccountId,
+		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			// u8转BoundedVec
-			let bounded_claim =
-			BoundedVec::<u8, <T as Config>::MaxClaimLength>::try_from(claim.clone()).map_err(|_| Error::<T>::ClaimTooLong)?;
 
 			// 只有存储在链上的才可以被转移，检查是否在链上
-			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(proof.owner == sender, Error::<T>::NostClaimOwner);
 
-			ensure!(owner == sender, Error::<T>::NostClaimOwner);
+			// 押金不足 claim_deposit() 的旧存证（迁移前创建或押金规则变更前创建），
+			// 转移前由当前所有者补足，使押金覆盖转移新增的历史记录
+			let deposit = proof.deposit.max(Self::claim_deposit());
+			T::Currency::reserve(&sender, deposit.saturating_sub(proof.deposit))?;
+			// 押金（包括元数据的押金）直接转为新所有者的押金
+			let metadata_deposit =
+				Metadata::<T>::get(&claim).map(|metadata| metadata.deposit).unwrap_or_default();
+			T::Currency::repatriate_reserved(
+				&sender,
+				&dest,
+				deposit.saturating_add(metadata_deposit),
+				BalanceStatus::Reserved,
+			)?;
+			// 记录历史所有者，超出上限时丢弃最早的记录
+			let now = frame_system::Pallet::<T>::block_number();
+			let time = T::Time::now();
+			ClaimHistory::<T>::mutate(&claim, |history| {
+				if history.len() as u32 >= T::MaxClaimHistory::get() && !history.is_empty() {
+					history.remove(0);
+				}
+				let _ = history.try_push(OwnershipRecord {
+					owner: sender.clone(),
+					since: proof.owned_since,
+					since_time: proof.owned_since_time,
+					until: now,
+					until_time: time,
+				});
+			});
+			Proofs::<T>::insert(
+				&claim,
+				Proof {
+					owner: dest.clone(),
+					owned_since: now,
+					owned_since_time: time,
+					deposit,
+					..proof
+				},
+			);
 
-			Proofs::<T>::insert(&bounded_claim, (dest, frame_system::Pallet::<T>::block_number()));
+			Self::deposit_event(Event::ClaimTransferred(sender, dest, claim, time));
 			Ok(().into())
 		}
+
+		/// Set a new expiry of a claim of the caller, at most `MaxClaimDuration` from now.
+		#[pallet::weight(T::WeightInfo::renew_claim())]
+		pub fn renew_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			expires_at: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let mut proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(proof.owner == sender, Error::<T>::NostClaimOwner);
+
+			if let Some(previous) = proof.expires_at {
+				Self::unschedule_expiry(claim, previous);
+			}
+			Self::schedule_expiry(claim, expires_at)?;
+			proof.expires_at = Some(expires_at);
+			Proofs::<T>::insert(&claim, proof);
+
+			Self::deposit_event(Event::ClaimRenewed(sender, claim, expires_at));
+			Ok(().into())
+		}
+
+		/// Reveal that a claim of the caller is the commitment `commitment(salt, document_hash)`,
+		/// proving the document was known when the claim was created.
+		#[pallet::weight(T::WeightInfo::reveal())]
+		pub fn reveal(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			salt: [u8; 32],
+			document_hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(proof.owner == sender, Error::<T>::NostClaimOwner);
+			ensure!(!Revelations::<T>::contains_key(&claim), Error::<T>::AlreadyRevealed);
+			ensure!(Self::commitment(&salt, &document_hash) == claim, Error::<T>::InvalidReveal);
+			Revelations::<T>::insert(&claim, document_hash);
+
+			Self::deposit_event(Event::ClaimRevealed(sender, claim, document_hash));
+			Ok(().into())
+		}
+
+		/// Propose a claim owned by the caller that `threshold` of `signers` must co-sign with
+		/// `cosign` before block `deadline`, reserving `joint_proposal_deposit()`. The proposal
+		/// lapses and the deposit is released if not enough signers co-sign in time.
+		#[pallet::weight(T::WeightInfo::propose_joint_claim(signers.len() as u32))]
+		pub fn propose_joint_claim(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			signers: Vec<T::AccountId>,
+			threshold: u32,
+			deadline: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			ensure!(!JointProposals::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			let signers: BoundedVec<_, _> =
+				signers.try_into().map_err(|_| Error::<T>::TooManyJointSigners)?;
+			for (index, signer) in signers.iter().enumerate() {
+				ensure!(!signers[..index].contains(signer), Error::<T>::DuplicateJointSigner);
+			}
+			ensure!(
+				threshold > 0 && threshold as usize <= signers.len(),
+				Error::<T>::InvalidThreshold
+			);
+			Self::schedule(
+				claim,
+				deadline,
+				T::MaxJointProposalDuration::get(),
+				Error::<T>::InvalidDeadline,
+			)?;
+			let deposit = Self::joint_proposal_deposit();
+			T::Currency::reserve(&sender, deposit)?;
+			JointProposals::<T>::insert(
+				&claim,
+				JointProposal {
+					proposer: sender.clone(),
+					signers,
+					approvals: Default::default(),
+					threshold,
+					deadline,
+					deposit,
+				},
+			);
+
+			Self::deposit_event(Event::JointClaimProposed(sender, claim, threshold, deadline));
+			Ok(().into())
+		}
+
+		/// Co-sign a joint claim proposal the caller is a signer of. The claim is created once
+		/// `threshold` signers co-signed, with the proposer as owner.
+		#[pallet::weight(T::WeightInfo::cosign(T::MaxJointSigners::get()))]
+		pub fn cosign(origin: OriginFor<T>, claim: T::Hash) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let mut proposal =
+				JointProposals::<T>::get(&claim).ok_or(Error::<T>::ProposalNotExist)?;
+			ensure!(proposal.signers.contains(&sender), Error::<T>::NotJointSigner);
+			ensure!(!proposal.approvals.contains(&sender), Error::<T>::AlreadyCosigned);
+			// Cannot fail: the approvals are a subset of the signers.
+			proposal
+				.approvals
+				.try_push(sender.clone())
+				.map_err(|_| Error::<T>::NotJointSigner)?;
+			Self::deposit_event(Event::JointClaimCosigned(sender, claim));
+
+			let signers = proposal.signers.len() as u32;
+			if (proposal.approvals.len() as u32) < proposal.threshold {
+				JointProposals::<T>::insert(&claim, proposal);
+				return Ok(Some(T::WeightInfo::cosign(signers)).into());
+			}
+
+			// 达到阈值，创建存证，多余的押金退还给提案人
+			JointProposals::<T>::remove(&claim);
+			Self::unschedule_expiry(claim, proposal.deadline);
+			let deposit = proposal.deposit.min(Self::joint_claim_deposit());
+			T::Currency::unreserve(&proposal.proposer, proposal.deposit.saturating_sub(deposit));
+			let now = frame_system::Pallet::<T>::block_number();
+			let time = T::Time::now();
+			Proofs::<T>::insert(
+				&claim,
+				Proof {
+					owner: proposal.proposer.clone(),
+					created_at: now,
+					created_time: time,
+					owned_since: now,
+					owned_since_time: time,
+					deposit,
+					expires_at: None,
+				},
+			);
+			JointSigners::<T>::insert(&claim, proposal.approvals);
+
+			Self::deposit_event(Event::JointClaimFinalized(proposal.proposer, claim, time));
+			Ok(Some(T::WeightInfo::cosign(signers)).into())
+		}
+
+		/// Set the metadata of a claim of the caller, replacing any previous one. Reserves
+		/// `ClaimDepositPerByte` per byte of `content_type`, `description` and `uri`, releasing
+		/// the deposit of the previous metadata.
+		#[pallet::weight(T::WeightInfo::set_claim_metadata())]
+		pub fn set_claim_metadata(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			content_type: Vec<u8>,
+			description: Vec<u8>,
+			uri: Vec<u8>,
+			version: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(proof.owner == sender, Error::<T>::NostClaimOwner);
+
+			let content_type: BoundedVec<_, _> =
+				content_type.try_into().map_err(|_| Error::<T>::ContentTypeTooLong)?;
+			let description: BoundedVec<_, _> =
+				description.try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
+			let uri: BoundedVec<_, _> = uri.try_into().map_err(|_| Error::<T>::UriTooLong)?;
+
+			// 按字节质押押金，只补交或退还与原元数据押金的差额
+			let bytes = content_type.len() + description.len() + uri.len();
+			let deposit = T::ClaimDepositPerByte::get().saturating_mul((bytes as u32).into());
+			let previous =
+				Metadata::<T>::get(&claim).map(|metadata| metadata.deposit).unwrap_or_default();
+			if deposit > previous {
+				T::Currency::reserve(&sender, deposit - previous)?;
+			} else {
+				T::Currency::unreserve(&sender, previous - deposit);
+			}
+			Metadata::<T>::insert(
+				&claim,
+				ClaimMetadata { content_type, description, uri, version, deposit },
+			);
+
+			Self::deposit_event(Event::ClaimMetadataSet(sender, claim));
+			Ok(().into())
+		}
+
+		/// Clear the metadata of a claim of the caller, releasing its deposit.
+		#[pallet::weight(T::WeightInfo::clear_claim_metadata())]
+		pub fn clear_claim_metadata(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let proof = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(proof.owner == sender, Error::<T>::NostClaimOwner);
+			let metadata = Metadata::<T>::take(&claim).ok_or(Error::<T>::MetadataNotExist)?;
+			T::Currency::unreserve(&sender, metadata.deposit);
+
+			Self::deposit_event(Event::ClaimMetadataCleared(sender, claim));
+			Ok(().into())
+		}
+
+		/// Attest an existing claim with the digest of a note, reserving `AttestationDeposit`.
+		/// Trusted attesters count toward `MaxTrustedAttestations`, others toward
+		/// `MaxAttestations`.
+		#[pallet::weight(T::WeightInfo::attest())]
+		pub fn attest(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+			note_hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let attester = ensure_signed(origin)?;
+
+			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			ensure!(
+				!Attestations::<T>::contains_key(&claim, &attester),
+				Error::<T>::AlreadyAttested
+			);
+			// 可信背书和非可信背书分别计数，非可信背书占满上限不影响可信背书
+			let trusted = TrustedAttesters::<T>::contains_key(&attester);
+			let max =
+				if trusted { T::MaxTrustedAttestations::get() } else { T::MaxAttestations::get() };
+			Self::count_attestation(&claim, trusted, |count| {
+				ensure!(*count < max, Error::<T>::TooManyAttestations);
+				*count += 1;
+				Ok(())
+			})?;
+
+			let deposit = T::AttestationDeposit::get();
+			T::Currency::reserve(&attester, deposit)?;
+			Attestations::<T>::insert(
+				&claim,
+				&attester,
+				Attestation {
+					block_number: frame_system::Pallet::<T>::block_number(),
+					note: note_hash,
+					deposit,
+					trusted,
+				},
+			);
+
+			Self::deposit_event(Event::ClaimAttested(attester, claim, note_hash));
+			Ok(().into())
+		}
+
+		/// Withdraw an attestation of the caller, releasing its deposit.
+		#[pallet::weight(T::WeightInfo::withdraw_attestation())]
+		pub fn withdraw_attestation(
+			origin: OriginFor<T>,
+			claim: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let attester = ensure_signed(origin)?;
+
+			let attestation = Attestations::<T>::take(&claim, &attester)
+				.ok_or(Error::<T>::AttestationNotExist)?;
+			Self::count_attestation(&claim, attestation.trusted, |count| {
+				*count = count.saturating_sub(1);
+				Ok(())
+			})?;
+			T::Currency::unreserve(&attester, attestation.deposit);
+
+			Self::deposit_event(Event::AttestationWithdrawn(attester, claim));
+			Ok(().into())
+		}
+
+		/// Add an account to the trusted attesters.
+		#[pallet::weight(T::WeightInfo::add_trusted_attester())]
+		pub fn add_trusted_attester(
+			origin: OriginFor<T>,
+			attester: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			ensure!(!TrustedAttesters::<T>::contains_key(&attester), Error::<T>::AlreadyTrusted);
+			TrustedAttesters::<T>::insert(&attester, ());
+
+			Self::deposit_event(Event::TrustedAttesterAdded(attester));
+			Ok(().into())
+		}
+
+		/// Remove an account from the trusted attesters. Its attestations are kept.
+		#[pallet::weight(T::WeightInfo::remove_trusted_attester())]
+		pub fn remove_trusted_attester(
+			origin: OriginFor<T>,
+			attester: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			TrustedAttesters::<T>::take(&attester).ok_or(Error::<T>::NotTrusted)?;
+
+			Self::deposit_event(Event::TrustedAttesterRemoved(attester));
+			Ok(().into())
+		}
+
+		/// Anchor the Merkle root of `leaf_count` claim digests, built with `merkle::root`,
+		/// reserving `batch_deposit()` from the caller. Every document of the batch is then
+		/// proven by its inclusion proof, see `verify_inclusion`.
+		#[pallet::weight(T::WeightInfo::anchor_batch())]
+		pub fn anchor_batch(
+			origin: OriginFor<T>,
+			merkle_root: T::Hash,
+			leaf_count: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::EmptyBatch);
+			ensure!(!Batches::<T>::contains_key(&merkle_root), Error::<T>::BatchAlreadyExist);
+			// 整批只质押一份押金
+			let deposit = Self::batch_deposit();
+			T::Currency::reserve(&sender, deposit)?;
+			Batches::<T>::insert(
+				&merkle_root,
+				Batch {
+					owner: sender.clone(),
+					leaf_count,
+					anchored_at: frame_system::Pallet::<T>::block_number(),
+					deposit,
+				},
+			);
+
+			Self::deposit_event(Event::BatchAnchored(sender, merkle_root, leaf_count));
+			Ok(().into())
+		}
+
+		/// Revoke a batch of the caller, releasing its deposit.
+		#[pallet::weight(T::WeightInfo::revoke_batch())]
+		pub fn revoke_batch(
+			origin: OriginFor<T>,
+			merkle_root: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let batch = Batches::<T>::get(&merkle_root).ok_or(Error::<T>::BatchNotExist)?;
+			ensure!(sender == batch.owner, Error::<T>::NostClaimOwner);
+			Batches::<T>::remove(&merkle_root);
+			T::Currency::unreserve(&sender, batch.deposit);
+
+			Self::deposit_event(Event::BatchRevoked(sender, merkle_root));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The canonical digest of a document, under which it is claimed. Compute it locally,
+		/// e.g. with `node-template poe hash <file>`, so the document never goes on-chain.
+		pub fn claim_hash(document: &[u8]) -> T::Hash {
+			T::Hashing::hash(document)
+		}
+
+		/// The salted commitment to a document digest, `hash(salt ++ document_hash)`. Claiming
+		/// it proves knowledge of the document without disclosing its digest, which is then
+		/// proven with `reveal`, or checked off-chain with `PoeApi::verify_commitment`.
+		pub fn commitment(salt: &[u8; 32], document_hash: &T::Hash) -> T::Hash {
+			T::Hashing::hash(&[&salt[..], document_hash.as_ref()].concat())
+		}
+
+		// Queue `claim` to expire in block `expires_at`.
+		fn schedule_expiry(claim: T::Hash, expires_at: T::BlockNumber) -> DispatchResult {
+			Self::schedule(claim, expires_at, T::MaxClaimDuration::get(), Error::<T>::InvalidExpiry)
+		}
+
+		// Queue `claim` in `ExpiringClaims` for block `at`, which must be after the current
+		// block and at most `max_duration` blocks after it, else fail with `invalid`.
+		fn schedule(
+			claim: T::Hash,
+			at: T::BlockNumber,
+			max_duration: T::BlockNumber,
+			invalid: Error<T>,
+		) -> DispatchResult {
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(at > now && at <= now.saturating_add(max_duration), invalid);
+			ExpiringClaims::<T>::try_mutate(at, |claims| claims.try_push(claim))
+				.map_err(|_| Error::<T>::TooManyExpiringClaims.into())
+		}
+
+		// Take `claim` out of the queue of block `expires_at`, freeing its slot.
+		fn unschedule_expiry(claim: T::Hash, expires_at: T::BlockNumber) {
+			ExpiringClaims::<T>::mutate(expires_at, |claims| claims.retain(|c| *c != claim));
+		}
+
+		// Remove a claim with its metadata, history, attestations, joint signers and revealed
+		// document, releasing all their deposits. Returns the number of attestations removed.
+		fn remove_claim(claim: T::Hash, proof: &ProofOf<T>) -> u32 {
+			Proofs::<T>::remove(&claim);
+			ClaimHistory::<T>::remove(&claim);
+			// 退还押金
+			T::Currency::unreserve(&proof.owner, proof.deposit);
+			if let Some(metadata) = Metadata::<T>::take(&claim) {
+				T::Currency::unreserve(&proof.owner, metadata.deposit);
+			}
+			JointSigners::<T>::remove(&claim);
+			Revelations::<T>::remove(&claim);
+			// 删除所有背书并退还背书人的押金
+			let attestations = AttestationCount::<T>::take(&claim)
+				.saturating_add(TrustedAttestationCount::<T>::take(&claim));
+			for (attester, attestation) in Attestations::<T>::drain_prefix(&claim) {
+				T::Currency::unreserve(&attester, attestation.deposit);
+			}
+			attestations
+		}
+
+		// Update the count of trusted or untrusted attestations of `claim`.
+		fn count_attestation(
+			claim: &T::Hash,
+			trusted: bool,
+			f: impl FnOnce(&mut u32) -> DispatchResult,
+		) -> DispatchResult {
+			if trusted {
+				TrustedAttestationCount::<T>::try_mutate(claim, f)
+			} else {
+				AttestationCount::<T>::try_mutate(claim, f)
+			}
+		}
+
+		// Remove `claim` if it still expires in block `block`. Returns the weight used.
+		fn expire_claim(claim: T::Hash, block: T::BlockNumber) -> Weight {
+			let proof = match Proofs::<T>::get(&claim) {
+				Some(proof) if proof.expires_at == Some(block) => proof,
+				// Revoked or renewed since.
+				_ => return T::DbWeight::get().reads(1),
+			};
+			let attestations = Self::remove_claim(claim, &proof);
+			Self::deposit_event(Event::ClaimExpired(proof.owner, claim));
+			T::WeightInfo::revoke_claim(attestations)
+		}
+
+		// Drop the joint claim proposal for `claim` if its deadline is block `block`. Returns the
+		// weight used.
+		fn lapse_joint_proposal(claim: T::Hash, block: T::BlockNumber) -> Weight {
+			let proposal = match JointProposals::<T>::get(&claim) {
+				Some(proposal) if proposal.deadline == block => proposal,
+				_ => return T::DbWeight::get().reads(1),
+			};
+			JointProposals::<T>::remove(&claim);
+			T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+			Self::deposit_event(Event::JointClaimLapsed(proposal.proposer, claim));
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		/// All attestations of a claim, with whether the attester is currently trusted.
+		pub fn claim_attestations(claim: T::Hash) -> Vec<(T::AccountId, AttestationOf<T>, bool)> {
+			Attestations::<T>::iter_prefix(&claim)
+				.map(|(attester, attestation)| {
+					let trusted = TrustedAttesters::<T>::contains_key(&attester);
+					(attester, attestation, trusted)
+				})
+				.collect()
+		}
+
+		/// Whether `proof` shows that the claim digest `leaf` is in the anchored batch `root`.
+		/// Proofs longer than any proof in a tree of the batch's `leaf_count` are rejected.
+		pub fn verify_inclusion(root: T::Hash, leaf: T::Hash, proof: &[T::Hash]) -> bool {
+			match Batches::<T>::get(&root) {
+				Some(batch) if proof.len() as u32 <= merkle::max_proof_len(batch.leaf_count) => {
+					merkle::verify::<T::Hashing>(&root, &leaf, proof)
+				},
+				_ => false,
+			}
+		}
+
+		/// Deposit reserved for a new claim: `ClaimDepositBase` plus `ClaimDepositPerByte` for
+		/// every byte of its key and value, of the longest `ClaimHistory` its transfers can add
+		/// and of its entry in `Revelations` once revealed.
+		pub fn claim_deposit() -> BalanceOf<T> {
+			let history = T::Hash::max_encoded_len()
+				.saturating_add(
+					BoundedVec::<OwnershipRecordOf<T>, T::MaxClaimHistory>::max_encoded_len(),
+				);
+			let revelation = T::Hash::max_encoded_len().saturating_mul(2);
+			Self::storage_deposit(
+				ProofOf::<T>::max_encoded_len()
+					.saturating_add(history)
+					.saturating_add(revelation),
+			)
+		}
+
+		/// Deposit kept for a finalized joint claim: `claim_deposit()` plus `ClaimDepositPerByte`
+		/// for every byte of its key and longest value in `JointSigners`.
+		pub fn joint_claim_deposit() -> BalanceOf<T> {
+			let signers = T::Hash::max_encoded_len()
+				.saturating_add(BoundedVec::<T::AccountId, T::MaxJointSigners>::max_encoded_len());
+			T::ClaimDepositPerByte::get()
+				.saturating_mul((signers as u32).into())
+				.saturating_add(Self::claim_deposit())
+		}
+
+		/// Deposit reserved for a new joint claim proposal, computed like `claim_deposit()` and
+		/// at least `joint_claim_deposit()`. Released when it lapses, or down to
+		/// `joint_claim_deposit()` when it is finalized.
+		pub fn joint_proposal_deposit() -> BalanceOf<T> {
+			Self::storage_deposit(JointProposal::<T>::max_encoded_len())
+				.max(Self::joint_claim_deposit())
+		}
+
+		/// Deposit reserved for a new batch, computed like `claim_deposit()`.
+		pub fn batch_deposit() -> BalanceOf<T> {
+			Self::storage_deposit(BatchOf::<T>::max_encoded_len())
+		}
+
+		// `ClaimDepositBase` plus `ClaimDepositPerByte` for a hash key and a value of `value_len`.
+		fn storage_deposit(value_len: usize) -> BalanceOf<T> {
+			let bytes = T::Hash::max_encoded_len().saturating_add(value_len);
+			T::ClaimDepositPerByte::get()
+				.saturating_mul((bytes as u32).into())
+				.saturating_add(T::ClaimDepositBase::get())
+		}
 	}
 }
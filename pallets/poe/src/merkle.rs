@@ -0,0 +1,74 @@
+//! Binary Merkle trees over claim digests, to anchor many documents with one `anchor_batch`.
+//!
+//! Leaves and inner nodes are hashed with different prefixes, so an inner node cannot be passed
+//! off as a leaf. The children of a node are hashed in sorted order, so a proof is only the list
+//! of siblings from the leaf up to the root. A node without a sibling moves up a level unchanged.
+
+use sp_runtime::traits::Hash;
+use sp_std::vec::Vec;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// The node of a leaf, e.g. the `claim_hash` of a document.
+pub fn hash_leaf<H: Hash>(leaf: &H::Output) -> H::Output {
+	H::hash(&[&[LEAF_PREFIX][..], leaf.as_ref()].concat())
+}
+
+/// The parent of two nodes, independent of their order.
+pub fn hash_node<H: Hash>(a: &H::Output, b: &H::Output) -> H::Output {
+	let (left, right) = if a.as_ref() <= b.as_ref() { (a, b) } else { (b, a) };
+	H::hash(&[&[NODE_PREFIX][..], left.as_ref(), right.as_ref()].concat())
+}
+
+// Pair up the nodes of a level; an odd last node moves up unchanged.
+fn parent_level<H: Hash>(level: &[H::Output]) -> Vec<H::Output> {
+	level
+		.chunks(2)
+		.map(|pair| match pair {
+			[left, right] => hash_node::<H>(left, right),
+			_ => pair[0],
+		})
+		.collect()
+}
+
+/// The root of the tree over `leaves`, in order. `None` if there are no leaves.
+pub fn root<H: Hash>(leaves: &[H::Output]) -> Option<H::Output> {
+	let mut level: Vec<_> = leaves.iter().map(hash_leaf::<H>).collect();
+	while level.len() > 1 {
+		level = parent_level::<H>(&level);
+	}
+	level.pop()
+}
+
+/// The siblings proving that `leaves[index]` is in the tree over `leaves`, from the leaf up.
+/// `None` if `index` is out of bounds.
+pub fn proof<H: Hash>(leaves: &[H::Output], mut index: usize) -> Option<Vec<H::Output>> {
+	if index >= leaves.len() {
+		return None;
+	}
+
+	let mut level: Vec<_> = leaves.iter().map(hash_leaf::<H>).collect();
+	let mut proof = Vec::new();
+	while level.len() > 1 {
+		if let Some(sibling) = level.get(index ^ 1) {
+			proof.push(*sibling);
+		}
+		level = parent_level::<H>(&level);
+		index /= 2;
+	}
+	Some(proof)
+}
+
+/// Whether `proof` shows that `leaf` is in the tree with `root`.
+pub fn verify<H: Hash>(root: &H::Output, leaf: &H::Output, proof: &[H::Output]) -> bool {
+	let node = proof
+		.iter()
+		.fold(hash_leaf::<H>(leaf), |node, sibling| hash_node::<H>(&node, sibling));
+	node == *root
+}
+
+/// The length of the longest proof in a tree of `leaf_count` leaves.
+pub fn max_proof_len(leaf_count: u32) -> u32 {
+	u64::from(leaf_count).next_power_of_two().trailing_zeros()
+}
@@ -0,0 +1,54 @@
+//! Storage migrations of the proof-of-existence pallet.
+
+/// Key claims by the digest of the document instead of the raw claim bytes, and store them as a
+/// `Proof`. They were created without a deposit, so their deposit is zero and nothing is released
+/// when they are revoked.
+pub mod v1 {
+	use crate::*;
+	use frame_support::{
+		migration::{put_storage_value, storage_key_iter},
+		pallet_prelude::*,
+		traits::{GetStorageVersion, PalletInfoAccess},
+		Blake2_128Concat, StorageHasher,
+	};
+	use sp_runtime::traits::Zero;
+	use sp_std::vec::Vec;
+
+	/// Migrate to storage version 1, if not done yet. Every claim is re-keyed by
+	/// `Pallet::claim_hash` of its bytes. Its block is both the creation and the last transfer
+	/// block, since the creation block of claims transferred before is lost, and the time of the
+	/// blocks before the upgrade is not known, so it is zero. The claims never expire.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return 0;
+		}
+
+		let name = <Pallet<T> as PalletInfoAccess>::name();
+		let pallet = name.as_bytes();
+		// The old keys were `BoundedVec<u8, MaxClaimLength>`, which encodes like a `Vec<u8>`.
+		// Collect first: the new keys share the prefix of the drained ones.
+		let claims: Vec<(Vec<u8>, (T::AccountId, T::BlockNumber))> =
+			storage_key_iter::<Vec<u8>, (T::AccountId, T::BlockNumber), Blake2_128Concat>(
+				pallet, b"Proofs",
+			)
+			.drain()
+			.collect();
+		for (claim, (owner, block_number)) in claims.iter().cloned() {
+			let proof: ProofOf<T> = Proof {
+				owner,
+				created_at: block_number,
+				created_time: Zero::zero(),
+				owned_since: block_number,
+				owned_since_time: Zero::zero(),
+				deposit: Zero::zero(),
+				expires_at: None,
+			};
+			let key = Blake2_128Concat::hash(&Pallet::<T>::claim_hash(&claim).encode());
+			put_storage_value(pallet, b"Proofs", &key, proof);
+		}
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		let count = claims.len() as Weight;
+		T::DbWeight::get().reads_writes(count + 1, 2 * count + 1)
+	}
+}
@@ -18,6 +18,8 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		PoeModule: pallet_poe::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -40,7 +42,7 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -49,12 +51,71 @@ impl system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+// 每个存证押金100，另按存储字节每字节1
+pub const DEPOSIT_BASE: u64 = 100;
+// 存证最长100个区块后过期
+pub const MAX_CLAIM_DURATION: u64 = 100;
+// 每个背书押金50
+pub const ATTESTATION_DEPOSIT: u64 = 50;
+
 impl pallet_poe::Config for Test {
-	type MaxClaimLength = ConstU32<512>;
 	type Event = Event;
+	type Currency = Balances;
+	type Time = Timestamp;
+	type ClaimDepositBase = ConstU64<DEPOSIT_BASE>;
+	type ClaimDepositPerByte = ConstU64<1>;
+	type MaxClaimDuration = ConstU64<MAX_CLAIM_DURATION>;
+	type MaxExpiringClaims = ConstU32<2>;
+	type MaxJointSigners = ConstU32<3>;
+	type MaxJointProposalDuration = ConstU64<50>;
+	type MaxContentTypeLength = ConstU32<16>;
+	type MaxDescriptionLength = ConstU32<32>;
+	type MaxUriLength = ConstU32<32>;
+	type MaxClaimHistory = ConstU32<2>;
+	type AttestationDeposit = ConstU64<ATTESTATION_DEPOSIT>;
+	type MaxAttestations = ConstU32<2>;
+	type MaxTrustedAttestations = ConstU32<1>;
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 10_000), (2, 10_000), (3, 10), (4, 10_000)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Events deposited by the poe pallet, in order.
+pub fn poe_events() -> Vec<pallet_poe::Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			Event::PoeModule(event) => Some(event),
+			_ => None,
+		})
+		.collect()
 }
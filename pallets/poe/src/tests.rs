@@ -1,51 +1,79 @@
 use super::*;
 use crate::{mock::*, Error};
-use frame_support::{ assert_noop, assert_ok, BoundedVec};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	migration::put_storage_value,
+	traits::{GetStorageVersion, Hooks, StorageVersion},
+	Blake2_128Concat, StorageHasher,
+};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+
+fn claim_of(document: &[u8]) -> H256 {
+	PoeModule::claim_hash(document)
+}
+
+// 设置区块和时间，每个区块6秒
+fn set_block(n: u64) {
+	System::set_block_number(n);
+	Timestamp::set_timestamp(n * 6_000);
+}
 
 // 创建存证 查看存储 重复创建 正常撤回，撤回失败
 #[test]
 fn create_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
 
-		let bounded_claim =
-			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
 		// 存储项断言
 		assert_eq!(
-			Proofs::<Test>::get(&bounded_claim),
-			Some((1, frame_system::Pallet::<Test>::block_number()))
+			Proofs::<Test>::get(&claim),
+			Some(Proof {
+				owner: 1,
+				created_at: 1,
+				created_time: 0,
+				owned_since: 1,
+				owned_since_time: 0,
+				deposit: PoeModule::claim_deposit(),
+				expires_at: None,
+			})
 		);
 
 		// 重复创建失败 已经存在
-		let claim2 = vec![0, 1];
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim2.clone()),
+			PoeModule::create_claim(Origin::signed(1), claim, None),
 			Error::<Test>::ProofAlreadyExist
 		);
-
 	})
 }
 // 撤销存证
 #[test]
 fn revoke_claim() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		let claim = claim_of(&[0, 1]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
 
-		let bounded_claim =
-			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
 		// 存储项断言
 		assert_eq!(
-			Proofs::<Test>::get(&bounded_claim),
-			Some((1, frame_system::Pallet::<Test>::block_number()))
+			Proofs::<Test>::get(&claim),
+			Some(Proof {
+				owner: 1,
+				created_at: 1,
+				created_time: 0,
+				owned_since: 1,
+				owned_since_time: 0,
+				deposit: PoeModule::claim_deposit(),
+				expires_at: None,
+			})
 		);
 		// 撤回存证
-		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
 
 		// 撤回失败不存在的claim
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(1), claim),
 			Error::<Test>::ClaimNotExist
 		);
 	});
@@ -54,9 +82,55 @@ fn revoke_claim() {
 #[test]
 fn transfer_claim_works() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1, 3, 4];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
-		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		let claim = claim_of(&[0, 1, 3, 4]);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 2);
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimTransferred(1, 2, claim, 0)));
+	});
+}
+
+// 转移记录：保留创建区块和时间，历史所有者按上限保留最近的记录
+#[test]
+fn transfer_claim_records_history() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1, 3, 4]);
+		set_block(1);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimCreated(1, claim, 6_000)));
+		set_block(5);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimTransferred(1, 2, claim, 30_000)));
+		set_block(8);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim, 3));
+
+		let proof = PoeModule::proofs(claim).unwrap();
+		assert_eq!((proof.owner, proof.created_at, proof.owned_since), (3, 1, 8));
+		assert_eq!((proof.created_time, proof.owned_since_time), (6_000, 48_000));
+		let record = |owner, since, until| OwnershipRecord {
+			owner,
+			since,
+			since_time: since * 6_000,
+			until,
+			until_time: until * 6_000,
+		};
+		assert_eq!(
+			PoeModule::claim_history(claim).into_inner(),
+			vec![record(1, 1, 5), record(2, 5, 8)]
+		);
+
+		// 超出 MaxClaimHistory 时丢弃最早的记录
+		set_block(9);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(3), claim, 1));
+		assert_eq!(
+			PoeModule::claim_history(claim).into_inner(),
+			vec![record(2, 5, 8), record(3, 8, 9)]
+		);
+		assert_eq!(PoeModule::proofs(claim).unwrap().created_at, 1);
+
+		// 撤销后历史一并删除
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert!(PoeModule::claim_history(claim).is_empty());
 	});
 }
 
@@ -64,16 +138,553 @@ fn transfer_claim_works() {
 #[test]
 fn transfer_claim_failed() {
 	new_test_ext().execute_with(|| {
-		let claim = vec![0, 1, 3, 4];
+		let claim = claim_of(&[0, 1, 3, 4]);
 		// 不存在错误
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2),
-			Error::<Test>::ClaimNotExist);
+			PoeModule::transfer_claim(Origin::signed(1), claim, 2),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(3), claim, 2),
+			Error::<Test>::NostClaimOwner
+		);
+	});
+}
+
+// 迁移：原始字节作为键的存证改为以摘要为键
+#[test]
+fn migration_hashes_claim_keys() {
+	new_test_ext().execute_with(|| {
+		let document = vec![0u8, 1, 3, 4];
+		put_storage_value(
+			b"PoeModule",
+			b"Proofs",
+			&Blake2_128Concat::hash(&document.encode()),
+			(1u64, 5u64),
+		);
+		StorageVersion::new(0).put::<PoeModule>();
+
+		migrations::v1::migrate::<Test>();
+
+		assert_eq!(PoeModule::on_chain_storage_version(), STORAGE_VERSION);
+		assert_eq!(
+			Proofs::<Test>::iter().collect::<Vec<_>>(),
+			vec![(
+				claim_of(&document),
+				Proof {
+					owner: 1,
+					created_at: 5,
+					created_time: 0,
+					owned_since: 5,
+					owned_since_time: 0,
+					deposit: 0,
+					expires_at: None
+				}
+			)]
+		);
+		// 迁移前的存证没有押金，撤销时不退还
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim_of(&document)));
+		assert_eq!(Balances::free_balance(1), 10_000);
+	});
+}
+
+// 押金：创建时质押，转移时转给新所有者，撤销时退还
+#[test]
+fn claim_deposit_follows_the_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let deposit = PoeModule::claim_deposit();
+		// 基础押金加上键（32字节）和值（6个8字节字段和9字节的过期区块）的存储字节，
+		// 历史记录的键和最多2条（每条5个8字节字段）历史记录的存储字节，
+		// 以及揭示记录的键和值（各32字节）的存储字节
+		assert_eq!(deposit, DEPOSIT_BASE + 32 + 48 + 9 + 32 + 1 + 2 * 40 + 32 + 32);
+
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_eq!(Balances::reserved_balance(1), deposit);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), deposit);
+		assert_eq!(Balances::free_balance(1), 10_000 - deposit);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 10_000 + deposit);
+
+		// 余额不足以支付押金时无法创建
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(3), claim, None),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+// 押金不足的旧存证：转移前由当前所有者补足，新所有者收到完整的押金
+#[test]
+fn transfer_tops_up_short_deposits() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let deposit = PoeModule::claim_deposit();
+		Proofs::<Test>::insert(
+			&claim,
+			Proof {
+				owner: 1,
+				created_at: 1,
+				created_time: 0,
+				owned_since: 1,
+				owned_since_time: 0,
+				deposit: 0,
+				expires_at: None,
+			},
+		);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(PoeModule::proofs(claim).unwrap().deposit, deposit);
+		assert_eq!(Balances::free_balance(1), 10_000 - deposit);
+		assert_eq!(Balances::reserved_balance(2), deposit);
+
+		// 押金已经足够时不再追加
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), claim, 4));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(4), deposit);
+		assert_eq!(PoeModule::claim_history(claim).len(), 2);
+
+		// 余额不足以补足押金时无法转移
+		Proofs::<Test>::mutate(&claim, |proof| {
+			let proof = proof.as_mut().unwrap();
+			proof.owner = 3;
+			proof.deposit = 0;
+		});
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(3), claim, 1),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+// 过期：到期区块删除存证并退还押金，续期后改在新的区块过期
+#[test]
+fn claims_expire_and_can_be_renewed() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let other = claim_of(&[2, 3]);
+		let deposit = PoeModule::claim_deposit();
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, Some(1)),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim, Some(2 + MAX_CLAIM_DURATION)),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, Some(10)));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), other, Some(10)));
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(2), claim_of(&[4]), Some(10)),
+			Error::<Test>::TooManyExpiringClaims
+		);
+		assert_ok!(PoeModule::attest(Origin::signed(2), other, claim));
+
+		// 续期：只有所有者可以续期，原区块的位置被释放
+		assert_noop!(
+			PoeModule::renew_claim(Origin::signed(2), claim, 20),
+			Error::<Test>::NostClaimOwner
+		);
+		assert_ok!(PoeModule::renew_claim(Origin::signed(1), claim, 20));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimRenewed(1, claim, 20)));
+		assert_eq!(PoeModule::expiring_claims(10).into_inner(), vec![other]);
+		assert_eq!(PoeModule::proofs(claim).unwrap().expires_at, Some(20));
+
+		PoeModule::on_initialize(10);
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimExpired(1, other)));
+		assert_eq!(PoeModule::proofs(other), None);
+		assert_eq!(PoeModule::attestations(other, 2), None);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(PoeModule::proofs(claim).is_some());
+
+		// 转移后由新所有者收回押金
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		PoeModule::on_initialize(20);
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimExpired(2, claim)));
+		assert_eq!(PoeModule::proofs(claim), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 10_000 + deposit);
+		assert!(PoeModule::expiring_claims(20).is_empty());
+	});
+}
+
+// 私密存证：存证加盐承诺，之后由所有者揭示文档摘要，撤销时删除揭示记录
+#[test]
+fn commitment_is_revealed_by_the_owner() {
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let document = claim_of(b"secret");
+		let claim = PoeModule::commitment(&salt, &document);
+		assert_ne!(claim, document);
+		assert_noop!(
+			PoeModule::reveal(Origin::signed(1), claim, salt, document),
+			Error::<Test>::ClaimNotExist
+		);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+
+		assert_noop!(
+			PoeModule::reveal(Origin::signed(1), claim, [8u8; 32], document),
+			Error::<Test>::InvalidReveal
+		);
+		assert_noop!(
+			PoeModule::reveal(Origin::signed(2), claim, salt, document),
+			Error::<Test>::NostClaimOwner
+		);
+		assert_ok!(PoeModule::reveal(Origin::signed(1), claim, salt, document));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimRevealed(1, claim, document)));
+		assert_eq!(PoeModule::revelations(claim), Some(document));
+		assert_noop!(
+			PoeModule::reveal(Origin::signed(1), claim, salt, document),
+			Error::<Test>::AlreadyRevealed
+		);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::revelations(claim), None);
+	});
+}
+
+// 元数据：只有所有者可以设置，按字节质押押金，随存证转移，撤销时退还
+#[test]
+fn claim_metadata_is_set_by_the_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let set = |who, content_type: &[u8], uri: &[u8], version| {
+			PoeModule::set_claim_metadata(
+				Origin::signed(who),
+				claim,
+				content_type.to_vec(),
+				b"contract".to_vec(),
+				uri.to_vec(),
+				version,
+			)
+		};
+		assert_noop!(set(1, b"application/pdf", b"", 1), Error::<Test>::ClaimNotExist);
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		let claim_deposit = PoeModule::claim_deposit();
+		assert_noop!(set(2, b"application/pdf", b"", 1), Error::<Test>::NostClaimOwner);
+		assert_noop!(set(1, &[b'a'; 17], b"", 1), Error::<Test>::ContentTypeTooLong);
+		assert_noop!(set(1, b"application/pdf", &[b'u'; 33], 1), Error::<Test>::UriTooLong);
+
+		// 15 + 8 字节
+		assert_ok!(set(1, b"application/pdf", b"", 1));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimMetadataSet(1, claim)));
+		assert_eq!(Balances::reserved_balance(1), claim_deposit + 23);
+		// 修改时只补交差额：15 + 8 + 12 字节
+		assert_ok!(set(1, b"application/pdf", b"ipfs://bafy1", 2));
+		let metadata = PoeModule::metadata(claim).unwrap();
+		assert_eq!((metadata.uri.into_inner(), metadata.version), (b"ipfs://bafy1".to_vec(), 2));
+		assert_eq!(Balances::reserved_balance(1), claim_deposit + 35);
+		// 缩短后退还多余的押金
+		assert_ok!(set(1, b"text/plain", b"", 3));
+		assert_eq!(Balances::reserved_balance(1), claim_deposit + 18);
+
+		// 押金随存证转移给新所有者
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), claim_deposit + 18);
+		assert_ok!(PoeModule::clear_claim_metadata(Origin::signed(2), claim));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimMetadataCleared(2, claim)));
+		assert_eq!(Balances::reserved_balance(2), claim_deposit);
+		assert_noop!(
+			PoeModule::clear_claim_metadata(Origin::signed(2), claim),
+			Error::<Test>::MetadataNotExist
+		);
+
+		assert_ok!(PoeModule::set_claim_metadata(
+			Origin::signed(2),
+			claim,
+			b"text/plain".to_vec(),
+			Vec::new(),
+			Vec::new(),
+			1
+		));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), claim));
+		assert_eq!(PoeModule::metadata(claim), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+// 联合存证：签名人共同签名达到阈值后创建存证，多余押金退还提案人
+#[test]
+fn joint_claim_is_finalized_at_the_threshold() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(b"contract");
+		let propose = |signers: Vec<u64>, threshold, deadline| {
+			PoeModule::propose_joint_claim(Origin::signed(1), claim, signers, threshold, deadline)
+		};
+		assert_noop!(propose(vec![2, 3, 4, 5], 2, 10), Error::<Test>::TooManyJointSigners);
+		assert_noop!(propose(vec![2, 3, 2], 2, 10), Error::<Test>::DuplicateJointSigner);
+		assert_noop!(propose(vec![2, 3], 0, 10), Error::<Test>::InvalidThreshold);
+		assert_noop!(propose(vec![2, 3], 3, 10), Error::<Test>::InvalidThreshold);
+		assert_noop!(propose(vec![2, 3], 2, 1), Error::<Test>::InvalidDeadline);
+		assert_noop!(propose(vec![2, 3], 2, 52), Error::<Test>::InvalidDeadline);
+
+		assert_ok!(propose(vec![1, 2, 3], 2, 10));
+		assert_eq!(poe_events().last(), Some(&crate::Event::JointClaimProposed(1, claim, 2, 10)));
+		let deposit = PoeModule::joint_proposal_deposit();
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		// 等待签名期间不能创建同一存证
+		assert_noop!(propose(vec![2], 1, 10), Error::<Test>::ProofAlreadyExist);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(2), claim, None),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		assert_noop!(PoeModule::cosign(Origin::signed(4), claim), Error::<Test>::NotJointSigner);
+		assert_ok!(PoeModule::cosign(Origin::signed(3), claim));
+		assert_eq!(poe_events().last(), Some(&crate::Event::JointClaimCosigned(3, claim)));
+		assert_noop!(PoeModule::cosign(Origin::signed(3), claim), Error::<Test>::AlreadyCosigned);
+		assert_eq!(PoeModule::proofs(claim), None);
+
+		set_block(4);
+		assert_ok!(PoeModule::cosign(Origin::signed(2), claim));
+		assert_eq!(poe_events().last(), Some(&crate::Event::JointClaimFinalized(1, claim, 24_000)));
+		// 存证押金还包括签名人列表的存储字节：键（32字节）、长度（1字节）和最多3个8字节的签名人
+		let joint_deposit = PoeModule::joint_claim_deposit();
+		assert_eq!(joint_deposit, PoeModule::claim_deposit() + 32 + 1 + 3 * 8);
+		let proof = PoeModule::proofs(claim).unwrap();
+		assert_eq!((proof.owner, proof.created_at, proof.deposit), (1, 4, joint_deposit));
+		assert_eq!(PoeModule::joint_signers(claim).unwrap().into_inner(), vec![3, 2]);
+		assert_eq!(PoeModule::joint_proposals(claim), None);
+		assert_eq!(Balances::reserved_balance(1), joint_deposit);
+		assert!(PoeModule::expiring_claims(10).is_empty());
+		assert_noop!(PoeModule::cosign(Origin::signed(1), claim), Error::<Test>::ProposalNotExist);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::joint_signers(claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 10_000);
+	});
+}
+
+// 联合存证：截止区块前签名不足时提案失效，退还押金
+#[test]
+fn joint_claim_lapses_after_the_deadline() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(b"contract");
+		assert_ok!(PoeModule::propose_joint_claim(Origin::signed(1), claim, vec![2, 3], 2, 10));
+		assert_ok!(PoeModule::cosign(Origin::signed(2), claim));
+
+		PoeModule::on_initialize(9);
+		assert!(PoeModule::joint_proposals(claim).is_some());
+		PoeModule::on_initialize(10);
+		assert_eq!(poe_events().last(), Some(&crate::Event::JointClaimLapsed(1, claim)));
+		assert_eq!(PoeModule::joint_proposals(claim), None);
+		assert_eq!(PoeModule::proofs(claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(PoeModule::cosign(Origin::signed(3), claim), Error::<Test>::ProposalNotExist);
+	});
+}
+
+// 背书：第三方为存证背书并质押押金，可撤回；撤销存证时一并删除
+#[test]
+fn attest_and_withdraw_attestation() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let note = claim_of(b"audit report");
+		assert_noop!(
+			PoeModule::attest(Origin::signed(2), claim, note),
+			Error::<Test>::ClaimNotExist
+		);
 
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_ok!(PoeModule::attest(Origin::signed(2), claim, note));
+		assert_eq!(poe_events().last(), Some(&crate::Event::ClaimAttested(2, claim, note)));
+		assert_eq!(
+			PoeModule::attestations(claim, 2),
+			Some(Attestation {
+				block_number: 1,
+				note,
+				deposit: ATTESTATION_DEPOSIT,
+				trusted: false,
+			})
+		);
+		assert_eq!(Balances::reserved_balance(2), ATTESTATION_DEPOSIT);
+		assert_noop!(
+			PoeModule::attest(Origin::signed(2), claim, note),
+			Error::<Test>::AlreadyAttested
+		);
+
+		assert_ok!(PoeModule::withdraw_attestation(Origin::signed(2), claim));
+		assert_eq!(poe_events().last(), Some(&crate::Event::AttestationWithdrawn(2, claim)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(PoeModule::attestation_count(claim), 0);
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(3), claim.clone(), 2),
+			PoeModule::withdraw_attestation(Origin::signed(2), claim),
+			Error::<Test>::AttestationNotExist
+		);
+
+		// 背书数量有上限
+		assert_ok!(PoeModule::attest(Origin::signed(1), claim, note));
+		assert_ok!(PoeModule::attest(Origin::signed(2), claim, note));
+		assert_noop!(
+			PoeModule::attest(Origin::signed(3), claim, note),
+			Error::<Test>::TooManyAttestations
+		);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::attestations(claim, 2), None);
+		assert_eq!(PoeModule::attestation_count(claim), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+// 可信背书人由root管理，查询背书时标注背书人当前是否可信
+#[test]
+fn trusted_attesters_are_managed_by_root() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let note = claim_of(b"notarised");
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_ok!(PoeModule::attest(Origin::signed(2), claim, note));
+
+		assert_noop!(
+			PoeModule::add_trusted_attester(Origin::signed(1), 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(PoeModule::add_trusted_attester(Origin::root(), 2));
+		assert_eq!(poe_events().last(), Some(&crate::Event::TrustedAttesterAdded(2)));
+		assert_noop!(
+			PoeModule::add_trusted_attester(Origin::root(), 2),
+			Error::<Test>::AlreadyTrusted
+		);
+		let attestation =
+			Attestation { block_number: 1, note, deposit: ATTESTATION_DEPOSIT, trusted: false };
+		assert_eq!(PoeModule::claim_attestations(claim), vec![(2, attestation.clone(), true)]);
+
+		assert_ok!(PoeModule::remove_trusted_attester(Origin::root(), 2));
+		assert_eq!(poe_events().last(), Some(&crate::Event::TrustedAttesterRemoved(2)));
+		assert_noop!(
+			PoeModule::remove_trusted_attester(Origin::root(), 2),
+			Error::<Test>::NotTrusted
+		);
+		assert_eq!(PoeModule::claim_attestations(claim), vec![(2, attestation, false)]);
+	});
+}
+
+// 非可信背书占满上限后，可信背书人仍然可以背书
+#[test]
+fn trusted_attesters_are_not_crowded_out() {
+	new_test_ext().execute_with(|| {
+		let claim = claim_of(&[0, 1]);
+		let note = claim_of(b"notarised");
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, None));
+		assert_ok!(PoeModule::add_trusted_attester(Origin::root(), 4));
+
+		assert_ok!(PoeModule::attest(Origin::signed(1), claim, note));
+		assert_ok!(PoeModule::attest(Origin::signed(2), claim, note));
+		assert_eq!(PoeModule::attestation_count(claim), 2);
+		assert_ok!(PoeModule::attest(Origin::signed(4), claim, note));
+		assert_eq!(PoeModule::trusted_attestation_count(claim), 1);
+		assert!(PoeModule::attestations(claim, 4).unwrap().trusted);
+
+		// 背书按背书时的信任状态计数，之后撤销信任不影响计数
+		assert_ok!(PoeModule::remove_trusted_attester(Origin::root(), 4));
+		assert_ok!(PoeModule::withdraw_attestation(Origin::signed(4), claim));
+		assert_eq!(PoeModule::trusted_attestation_count(claim), 0);
+		assert_eq!(PoeModule::attestation_count(claim), 2);
+		assert_noop!(
+			PoeModule::attest(Origin::signed(4), claim, note),
+			Error::<Test>::TooManyAttestations
+		);
+
+		// 可信背书同样有上限
+		assert_ok!(PoeModule::add_trusted_attester(Origin::root(), 4));
+		assert_ok!(PoeModule::add_trusted_attester(Origin::root(), 3));
+		assert_ok!(PoeModule::attest(Origin::signed(4), claim, note));
+		assert_noop!(
+			PoeModule::attest(Origin::signed(3), claim, note),
+			Error::<Test>::TooManyAttestations
+		);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+		assert_eq!(PoeModule::attestation_count(claim), 0);
+		assert_eq!(PoeModule::trusted_attestation_count(claim), 0);
+		assert_eq!(Balances::reserved_balance(4), 0);
+	});
+}
+
+// Merkle树：每个叶子的证明都能验证，且不超过树高；错误的叶子或根验证失败
+#[test]
+fn merkle_proofs_verify() {
+	let leaves: Vec<H256> = (0u8..5).map(|i| claim_of(&[i])).collect();
+	for count in 1..=leaves.len() {
+		let leaves = &leaves[..count];
+		let root = merkle::root::<BlakeTwo256>(leaves).unwrap();
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = merkle::proof::<BlakeTwo256>(leaves, index).unwrap();
+			assert!(proof.len() as u32 <= merkle::max_proof_len(count as u32));
+			assert!(merkle::verify::<BlakeTwo256>(&root, leaf, &proof));
+			assert!(!merkle::verify::<BlakeTwo256>(&root, &claim_of(b"other"), &proof));
+		}
+	}
+	assert_eq!(merkle::root::<BlakeTwo256>(&[]), None);
+	assert_eq!(merkle::proof::<BlakeTwo256>(&leaves, leaves.len()), None);
+
+	// 内部节点不能冒充叶子
+	let root = merkle::root::<BlakeTwo256>(&leaves[..2]).unwrap();
+	let inner = merkle::hash_node::<BlakeTwo256>(
+		&merkle::hash_leaf::<BlakeTwo256>(&leaves[0]),
+		&merkle::hash_leaf::<BlakeTwo256>(&leaves[1]),
+	);
+	assert_eq!(root, inner);
+	assert!(!merkle::verify::<BlakeTwo256>(&root, &inner, &[]));
+}
+
+// 批量存证：锚定Merkle根并质押押金，按包含证明验证文档，撤销时退还押金
+#[test]
+fn anchor_batch_and_verify_inclusion() {
+	new_test_ext().execute_with(|| {
+		let leaves: Vec<H256> = (0u8..3).map(|i| claim_of(&[i])).collect();
+		let root = merkle::root::<BlakeTwo256>(&leaves).unwrap();
+		let proof = merkle::proof::<BlakeTwo256>(&leaves, 2).unwrap();
+		// 未锚定的根无法验证
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], &proof));
+
+		assert_noop!(
+			PoeModule::anchor_batch(Origin::signed(1), root, 0),
+			Error::<Test>::EmptyBatch
+		);
+		assert_ok!(PoeModule::anchor_batch(Origin::signed(1), root, 3));
+		assert_eq!(poe_events().last(), Some(&crate::Event::BatchAnchored(1, root, 3)));
+		let deposit = PoeModule::batch_deposit();
+		assert_eq!(
+			PoeModule::batches(root),
+			Some(Batch { owner: 1, leaf_count: 3, anchored_at: 1, deposit })
+		);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+		assert_noop!(
+			PoeModule::anchor_batch(Origin::signed(2), root, 3),
+			Error::<Test>::BatchAlreadyExist
+		);
+
+		assert!(PoeModule::verify_inclusion(root, leaves[2], &proof));
+		assert!(!PoeModule::verify_inclusion(root, claim_of(b"other"), &proof));
+		// 超过树高的证明被拒绝
+		let mut padded = proof.clone();
+		padded.extend(merkle::proof::<BlakeTwo256>(&leaves, 0).unwrap());
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], &padded));
+
+		assert_noop!(
+			PoeModule::revoke_batch(Origin::signed(2), root),
 			Error::<Test>::NostClaimOwner
 		);
+		assert_ok!(PoeModule::revoke_batch(Origin::signed(1), root));
+		assert_eq!(poe_events().last(), Some(&crate::Event::BatchRevoked(1, root)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!PoeModule::verify_inclusion(root, leaves[2], &proof));
+		assert_noop!(
+			PoeModule::revoke_batch(Origin::signed(1), root),
+			Error::<Test>::BatchNotExist
+		);
 	});
 }
@@ -0,0 +1,319 @@
+//! Weights for pallet_poe.
+//!
+//! PLACEHOLDERS: these values were NOT generated by `benchmark pallet`. They are rough estimates
+//! from the storage accesses listed above each function, with made-up round base weights, and
+//! must not be relied on for fee or block-limit safety. Replace this file with the benchmark
+//! output before a production release.
+//!
+//! Generate it on the reference hardware with `scripts/benchmark_poe.sh`, which builds the node
+//! with `--features runtime-benchmarks` and runs:
+//!
+//! ```sh
+//! ./target/release/node-template benchmark pallet \
+//! 	--chain dev --execution wasm --wasm-execution compiled \
+//! 	--pallet pallet_poe --extrinsic '*' --steps 50 --repeat 20 \
+//! 	--output pallets/poe/src/weights.rs
+//! ```
+//!
+//! and keep the `WeightInfo` trait and the `()` implementation used by the tests.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_poe.
+pub trait WeightInfo {
+	fn create_claim() -> Weight;
+	fn revoke_claim(a: u32) -> Weight;
+	fn renew_claim() -> Weight;
+	fn reveal() -> Weight;
+	fn transfer_claim() -> Weight;
+	fn set_claim_metadata() -> Weight;
+	fn clear_claim_metadata() -> Weight;
+	fn propose_joint_claim(s: u32) -> Weight;
+	fn cosign(s: u32) -> Weight;
+	fn attest() -> Weight;
+	fn withdraw_attestation() -> Weight;
+	fn add_trusted_attester() -> Weight;
+	fn remove_trusted_attester() -> Weight;
+	fn anchor_batch() -> Weight;
+	fn revoke_batch() -> Weight;
+}
+
+/// Placeholder weights for pallet_poe, not measured; see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn create_claim() -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: PoeModule TrustedAttestationCount (r:1 w:1)
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule ClaimHistory (r:0 w:1)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: PoeModule JointSigners (r:0 w:1)
+	// Storage: PoeModule Revelations (r:0 w:1)
+	fn revoke_claim(a: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((14_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:2 w:2)
+	fn renew_claim() -> Weight {
+		(29_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Revelations (r:1 w:1)
+	fn reveal() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: PoeModule ClaimHistory (r:1 w:1)
+	// Storage: PoeModule Metadata (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn transfer_claim() -> Weight {
+		(49_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn set_claim_metadata() -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn clear_claim_metadata() -> Weight {
+		(29_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule JointProposals (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn propose_joint_claim(s: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule JointProposals (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: PoeModule Proofs (r:0 w:1)
+	// Storage: PoeModule JointSigners (r:0 w:1)
+	fn cosign(s: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule TrustedAttesters (r:1 w:0)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn attest() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_attestation() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule TrustedAttesters (r:1 w:1)
+	fn add_trusted_attester() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule TrustedAttesters (r:1 w:1)
+	fn remove_trusted_attester() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule Batches (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn anchor_batch() -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Batches (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_batch() -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn create_claim() -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: PoeModule TrustedAttestationCount (r:1 w:1)
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule ClaimHistory (r:0 w:1)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: PoeModule JointSigners (r:0 w:1)
+	// Storage: PoeModule Revelations (r:0 w:1)
+	fn revoke_claim(a: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((14_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:2 w:2)
+	fn renew_claim() -> Weight {
+		(29_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Revelations (r:1 w:1)
+	fn reveal() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: PoeModule ClaimHistory (r:1 w:1)
+	// Storage: PoeModule Metadata (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	fn transfer_claim() -> Weight {
+		(49_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn set_claim_metadata() -> Weight {
+		(33_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Metadata (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn clear_claim_metadata() -> Weight {
+		(29_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule JointProposals (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn propose_joint_claim(s: u32) -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule JointProposals (r:1 w:1)
+	// Storage: PoeModule ExpiringClaims (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: PoeModule Proofs (r:0 w:1)
+	// Storage: PoeModule JointSigners (r:0 w:1)
+	fn cosign(s: u32) -> Weight {
+		(41_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule TrustedAttesters (r:1 w:0)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn attest() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule Attestations (r:1 w:1)
+	// Storage: PoeModule AttestationCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_attestation() -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: PoeModule TrustedAttesters (r:1 w:1)
+	fn add_trusted_attester() -> Weight {
+		(16_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule TrustedAttesters (r:1 w:1)
+	fn remove_trusted_attester() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: PoeModule Batches (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn anchor_batch() -> Weight {
+		(27_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: PoeModule Batches (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn revoke_batch() -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+}
@@ -14,8 +14,11 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount,
+		NumberFor, Verify,
+	},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 use sp_std::prelude::*;
@@ -33,7 +36,7 @@ pub use frame_support::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
@@ -65,6 +68,9 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -101,7 +107,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 127,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -227,8 +233,7 @@ impl pallet_grandpa::Config for Runtime {
 }
 
 impl pallet_timestamp::Config for Runtime {
-	/// A timestamp: milliseconds since the unix epoch.
-	type Moment = u64;
+	type Moment = Moment;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = ConstU64<{ SLOT_DURATION / 2 }>;
 	type WeightInfo = ();
@@ -256,6 +261,31 @@ impl pallet_transaction_payment::Config for Runtime {
 	type FeeMultiplierUpdate = ();
 }
 
+parameter_types! {
+	pub const AssetDeposit: Balance = 100_000;
+	pub const AssetAccountDeposit: Balance = 100;
+	pub const MetadataDepositBase: Balance = 1_000;
+	pub const MetadataDepositPerByte: Balance = 10;
+	pub const ApprovalDeposit: Balance = 100;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -265,25 +295,160 @@ impl pallet_sudo::Config for Runtime {
 impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
+parameter_types! {
+	// 存证押金：每个存证1_000，另按存储字节每字节10
+	pub const PoeClaimDepositBase: Balance = 1_000;
+	pub const PoeClaimDepositPerByte: Balance = 10;
+	// 存证最长一年后过期，每个区块最多50个存证过期
+	pub const PoeMaxClaimDuration: BlockNumber = 365 * DAYS;
+	pub const PoeMaxExpiringClaims: u32 = 50;
+	// 联合存证最多20个签名人，提案最长30天后失效
+	pub const PoeMaxJointSigners: u32 = 20;
+	pub const PoeMaxJointProposalDuration: BlockNumber = 30 * DAYS;
+	// 元数据：类型最长64字节，描述和URI最长256字节
+	pub const PoeMaxContentTypeLength: u32 = 64;
+	pub const PoeMaxDescriptionLength: u32 = 256;
+	pub const PoeMaxUriLength: u32 = 256;
+	// 每个存证最多保留最近的100个历史所有者
+	pub const PoeMaxClaimHistory: u32 = 100;
+	// 每个背书押金1_000，每个存证最多50个非可信背书和10个可信背书
+	pub const PoeAttestationDeposit: Balance = 1_000;
+	pub const PoeMaxAttestations: u32 = 50;
+	pub const PoeMaxTrustedAttestations: u32 = 10;
+}
 impl pallet_poe::Config for Runtime {
-	type MaxClaimLength = ConstU32<512>; // 满足get 类型的数组u32
 	type Event = Event;
+	type Currency = Balances;
+	type Time = Timestamp;
+	type ClaimDepositBase = PoeClaimDepositBase;
+	type ClaimDepositPerByte = PoeClaimDepositPerByte;
+	type MaxClaimDuration = PoeMaxClaimDuration;
+	type MaxExpiringClaims = PoeMaxExpiringClaims;
+	type MaxJointSigners = PoeMaxJointSigners;
+	type MaxJointProposalDuration = PoeMaxJointProposalDuration;
+	type MaxContentTypeLength = PoeMaxContentTypeLength;
+	type MaxDescriptionLength = PoeMaxDescriptionLength;
+	type MaxUriLength = PoeMaxUriLength;
+	type MaxClaimHistory = PoeMaxClaimHistory;
+	type AttestationDeposit = PoeAttestationDeposit;
+	type MaxAttestations = PoeMaxAttestations;
+	type MaxTrustedAttestations = PoeMaxTrustedAttestations;
+	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
 	// one can own at most 9,999 Kitties
 	pub const MaxKittyOwned: u32 = 9999;
+	// 默认每个账户最多拥有100只，root可以为指定账户提高上限（不超过MaxKittyOwned）
+	pub const DefaultKittyQuota: u32 = 100;
 
 	// 定义创建每只Kitty时需要质押原生的token数量
 	pub const KittyStake: u128 = 1_000;
+
+	// Kitty总量上限，以及每个区块最多铸造的数量
+	pub const MaxTotalKitties: u32 = 1_000_000;
+	pub const MaxMintsPerBlock: u32 = 10;
+
+	// 铸造价格曲线：第n只Kitty的价格为 KittyMintPriceBase + KittyMintPriceStep * n
+	pub const KittyMintPriceBase: u128 = 1_000;
+	pub const KittyMintPriceStep: u128 = 10;
+	// 铸造费用的收款账户
+	pub KittyMintProceedsAccount: AccountId = PalletId(*b"py/kmint").into_account_truncating();
+
+	// 市场统计按天汇总
+	pub const KittyStatsPeriod: BlockNumber = DAYS;
+
+	// 同一区块内最多过期的挂单数量
+	pub const MaxExpiringListings: u32 = 256;
+
+	// 创建集合时需要质押的数量
+	pub const KittyCollectionDeposit: u128 = 10_000;
+
+	// 幼年期3天，成年期60天，之后进入老年期；只有成年的Kitty可以繁殖
+	pub const KittyKittenDuration: BlockNumber = 3 * DAYS;
+	pub const KittyAdultDuration: BlockNumber = 60 * DAYS;
 }
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type KittyRandomness = RandomnessCollectiveFlip;
 	type Currency = Balances;
 	type MaxKittyOwned = self::MaxKittyOwned;
+	type KittyQuota = pallet_kitties::FixedQuota<DefaultKittyQuota>;
 	type KittyIndex = u32; //定义Kitty的索引ID类型
+	type CollectionId = u32;
+	type CollectionDeposit = KittyCollectionDeposit;
 	type KittyStake = KittyStake; //引入KittyStake常量
+	type MaxTotalKitties = MaxTotalKitties;
+	type MaxMintsPerBlock = MaxMintsPerBlock;
+	type MintPriceBase = KittyMintPriceBase;
+	type MintPriceStep = KittyMintPriceStep;
+	type MintProceedsAccount = KittyMintProceedsAccount;
+	type StatsPeriod = KittyStatsPeriod;
+	type ListingAssetId = u32;
+	type ListingAssets = Assets;
+	type MaxExpiringListings = MaxExpiringListings;
+	type KittenDuration = KittyKittenDuration;
+	type AdultDuration = KittyAdultDuration;
+	// 链下挂单使用与交易相同的签名类型，按卖家账户验证
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+}
+
+parameter_types! {
+	// 质押池奖励账户由PalletId派生
+	pub const KittyStakingPalletId: PalletId = PalletId(*b"py/kstak");
+	// 每个区块分配给所有质押者的奖励
+	pub const KittyRewardPerBlock: Balance = 100;
+}
+impl pallet_kitty_staking::Config for Runtime {
+	type Event = Event;
+	type PalletId = KittyStakingPalletId;
+	type RewardPerBlock = KittyRewardPerBlock;
+}
+
+parameter_types! {
+	// 接受挑战后等待若干区块再揭示随机数
+	pub const ArenaRevealDelay: BlockNumber = 3;
+	// 挑战者在挑战结束前质押的押金
+	pub const ArenaChallengeDeposit: Balance = 1_000;
+	pub const ArenaExperiencePerWin: u32 = 100;
+	// 排行榜保留前100名
+	pub const ArenaLeaderboardSize: u32 = 100;
+}
+impl pallet_kitty_arena::Config for Runtime {
+	type Event = Event;
+	type ArenaRandomness = RandomnessCollectiveFlip;
+	type RevealDelay = ArenaRevealDelay;
+	type ChallengeDeposit = ArenaChallengeDeposit;
+	type ExperiencePerWin = ArenaExperiencePerWin;
+	type LeaderboardSize = ArenaLeaderboardSize;
+}
+
+parameter_types! {
+	// 份额资产的管理账户和买断资金账户由PalletId派生
+	pub const KittyFractionsPalletId: PalletId = PalletId(*b"py/kfrac");
+	pub const KittyBuyoutPeriod: BlockNumber = DAYS;
+}
+impl pallet_kitty_fractions::Config for Runtime {
+	type Event = Event;
+	type AssetId = u32;
+	type Assets = Assets;
+	type PalletId = KittyFractionsPalletId;
+	type BuyoutPeriod = KittyBuyoutPeriod;
+}
+
+parameter_types! {
+	// 礼物可领取的时长，以及同一区块最多过期的礼物数量
+	pub const KittyGiftDuration: BlockNumber = 7 * DAYS;
+	pub const MaxExpiringGifts: u32 = 100;
+	// 无签名领取交易在交易池中的优先级
+	pub const KittyGiftUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+impl pallet_kitty_gifts::Config for Runtime {
+	type Event = Event;
+	type GiftDuration = KittyGiftDuration;
+	type MaxExpiringGifts = MaxExpiringGifts;
+	type UnsignedPriority = KittyGiftUnsignedPriority;
 }
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
@@ -300,10 +465,15 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
+		Assets: pallet_assets,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
 		PoeModule: pallet_poe,
 		KittiesModule: pallet_kitties,
+		KittyStaking: pallet_kitty_staking,
+		KittyArena: pallet_kitty_arena,
+		KittyFractions: pallet_kitty_fractions,
+		KittyGifts: pallet_kitty_gifts,
 	}
 );
 
@@ -346,9 +516,11 @@ mod benches {
 	define_benchmarks!(
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
+		[pallet_assets, Assets]
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
+		[pallet_poe, PoeModule]
 	);
 }
 
@@ -483,6 +655,72 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, AccountId, (u32, u32), Balance, BlockNumber> for Runtime {
+		fn kitty(kitty_id: (u32, u32)) -> Option<([u8; 16], pallet_kitties_runtime_api::Gender)> {
+			KittiesModule::kitties(kitty_id).map(|kitty| (kitty.dna, kitty.gender))
+		}
+
+		fn kitty_stage(kitty_id: (u32, u32)) -> Option<pallet_kitties_runtime_api::LifeStage> {
+			KittiesModule::kitties(kitty_id).map(|kitty| KittiesModule::life_stage(&kitty))
+		}
+
+		fn last_sale(
+			kitty_id: (u32, u32),
+		) -> Option<pallet_kitties_runtime_api::SaleRecord<AccountId, Balance, BlockNumber>> {
+			KittiesModule::last_sales(kitty_id)
+		}
+
+		fn market_stats(period: BlockNumber) -> pallet_kitties_runtime_api::MarketPeriodStats<Balance> {
+			KittiesModule::period_stats(period)
+		}
+
+		fn current_period() -> BlockNumber {
+			KittiesModule::current_period()
+		}
+	}
+
+	impl pallet_poe_runtime_api::PoeApi<Block, AccountId, Hash, Balance, BlockNumber, Moment>
+		for Runtime
+	{
+		fn claim(
+			claim: Hash,
+		) -> Option<pallet_poe_runtime_api::Proof<AccountId, Balance, BlockNumber, Moment>> {
+			PoeModule::proofs(claim)
+		}
+
+		fn claim_history(
+			claim: Hash,
+		) -> Vec<pallet_poe_runtime_api::OwnershipRecord<AccountId, BlockNumber, Moment>> {
+			PoeModule::claim_history(claim).into_inner()
+		}
+
+		fn attestations(
+			claim: Hash,
+		) -> Vec<(AccountId, pallet_poe_runtime_api::Attestation<BlockNumber, Hash, Balance>, bool)> {
+			PoeModule::claim_attestations(claim)
+		}
+
+		fn joint_signers(claim: Hash) -> Vec<AccountId> {
+			PoeModule::joint_signers(claim).map(|signers| signers.into_inner()).unwrap_or_default()
+		}
+
+		fn revealed_document(claim: Hash) -> Option<Hash> {
+			PoeModule::revelations(claim)
+		}
+
+		fn verify_commitment(claim: Hash, salt: [u8; 32], document_hash: Hash) -> bool {
+			PoeModule::commitment(&salt, &document_hash) == claim
+		}
+
+		fn batch(root: Hash) -> Option<pallet_poe_runtime_api::Batch<AccountId, Balance, BlockNumber>> {
+			PoeModule::batches(root)
+		}
+
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_inclusion(root, leaf, &proof)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
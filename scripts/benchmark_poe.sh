@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems, on the reference hardware
+set -e
+
+echo "*** Building the node with the runtime benchmarks"
+
+cargo build --release --features runtime-benchmarks
+
+echo "*** Benchmarking every call of pallet_poe into pallets/poe/src/weights.rs"
+
+./target/release/node-template benchmark pallet \
+	--chain dev --execution wasm --wasm-execution compiled \
+	--pallet pallet_poe --extrinsic '*' --steps 50 --repeat 20 \
+	--output pallets/poe/src/weights.rs